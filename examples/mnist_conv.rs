@@ -22,8 +22,20 @@ fn mlp_layer<T: Into<Variable>>(input: T, input_size: usize, output_size: usize)
 
 fn conv_layer<T: Into<Variable>>(input: T, input_channels: usize, output_channels: usize, filter_size: usize) -> Function {
     let w = Variable::parameter(&Shape::new(&vec!(filter_size, filter_size, input_channels, output_channels)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
-    let b = Variable::parameter(&Shape::new(&vec!(1, 1, output_channels)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
-    return relu(plus(&b, convolution(&w, input, &Shape::new(&vec!(1, 1, input_channels)))));
+    let conv = convolution(&w, input, &Shape::new(&vec!(1, 1, input_channels)));
+    // No convolution bias: batch normalization subtracts the per-channel mean and adds its own bias
+    return relu(batch_normalization_layer(conv));
+}
+
+/// Spatial batch normalization, running statistics are updated by trainer and used by evaluate
+fn batch_normalization_layer<T: Into<Variable>>(input: T) -> Function {
+    let channels = Shape::new(&vec!(Shape::INFERRED_DIMENSION));
+    let scale = Variable::parameter(&channels, &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+    let bias = Variable::parameter(&channels, &ParameterInitializer::constant(0.), DeviceDescriptor::cpu());
+    let running_mean = Variable::constant_repeat(&channels, 0.);
+    let running_variance = Variable::constant_repeat(&channels, 0.);
+    let running_count = Variable::constant_scalar(0.);
+    return batch_normalization(input, &scale, &bias, &running_mean, &running_variance, &running_count, &BatchNormalizationOptions::new().spatial(true).use_cudnn_engine(false));
 }
 
 fn pooling_layer<T: Into<Variable>>(input: T, pool_size: usize) -> Function {
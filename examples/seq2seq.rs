@@ -47,25 +47,30 @@ fn build_vocab(tokens: &[String]) -> (HashMap<String, usize>, Vec<usize>) {
 fn gru_layer<T: Into<Variable>>(input: T, input_size: usize, hidden_size: usize, init_value: Option<&Variable>) -> Function {
     let inputv = input.into();
 
-    let placeholder = Variable::placeholder(&Shape::new(vec!(hidden_size)));
+    let prev_state = Variable::placeholder(&Shape::new(vec!(hidden_size)));
+    let cell_input = Variable::placeholder(&Shape::new(vec!(input_size)));
 
     let one = Variable::constant_scalar(1.0);
     let wou = Variable::parameter(&Shape::new(vec!(hidden_size, hidden_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
     let wiu = Variable::parameter(&Shape::new(vec!(hidden_size, input_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
     let bu = Variable::parameter(&Shape::new(vec!(hidden_size)), &ParameterInitializer::constant(-1.0), DeviceDescriptor::cpu());
-    let update_gate = sigmoid(plus(plus(times(&wou, &placeholder), times(&wiu, &inputv)), &bu));
+    let update_gate = sigmoid(plus(plus(times(&wou, &prev_state), times(&wiu, &cell_input)), &bu));
 
     let wor = Variable::parameter(&Shape::new(vec!(hidden_size, hidden_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
     let wir = Variable::parameter(&Shape::new(vec!(hidden_size, input_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
     let br = Variable::parameter(&Shape::new(vec!(hidden_size)), &ParameterInitializer::constant(1.0), DeviceDescriptor::cpu());
-    let reset_gate = sigmoid(plus(plus(times(&wor, &placeholder), times(&wir, &inputv)), &br));
+    let reset_gate = sigmoid(plus(plus(times(&wor, &prev_state), times(&wir, &cell_input)), &br));
 
     let woo = Variable::parameter(&Shape::new(vec!(hidden_size, hidden_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
     let wio = Variable::parameter(&Shape::new(vec!(hidden_size, input_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
     let bo = Variable::parameter(&Shape::new(vec!(hidden_size)), &ParameterInitializer::glorot_uniform(), DeviceDescriptor::cpu());
-    let new_value = tanh(plus(plus(times(&wio, &inputv), &bo), element_times(&reset_gate, times(&woo, &placeholder))));
+    let new_value = tanh(plus(plus(times(&wio, &cell_input), &bo), element_times(&reset_gate, times(&woo, &prev_state))));
+
+    let cell = plus(element_times(&update_gate, new_value), element_times(minus(one, &update_gate), &prev_state));
 
-    let output = plus(element_times(&update_gate, new_value), element_times(minus(one, &update_gate), &placeholder));
+    // GRU cell is shown as single "GRU" node in saved model, recurrence is added around it
+    let placeholder = Variable::placeholder(&Shape::new(vec!(hidden_size)));
+    let output = Function::as_block(&cell, &[(&prev_state, &*placeholder), (&cell_input, &inputv)], "GRU", "gru");
 
     let placeholder_replacement = match init_value {
         Some(var) => past_value_with_init(&output, broadcast_as(var, &inputv)),
@@ -163,8 +168,8 @@ fn main() {
             decoder_predictions.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
 
             println!("excepted  {:?}", decoder_labels_batch[0]);
-            let preds = outdatamap.get(&decoder_predictions).unwrap().to_vec();
-            println!("predicted {:?}", &preds[..decoder_labels_batch[0].len()]);
+            let preds = outdatamap.get(&decoder_probs).unwrap().to_one_hot_sequences();
+            println!("predicted {:?}", preds[0]);
             println!("");
 
             loss_sum = 0.0;
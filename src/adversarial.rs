@@ -0,0 +1,125 @@
+//! Input gradients (saliency maps) and adversarial examples.
+//!
+//! Every helper takes `arguments` binding all inputs of the function (including `input` itself) and works
+//! with f32 Values only (`try_` variants return `Error` for other data types).
+use function::Function;
+use variable::Variable;
+use value::Value;
+use data_map::DataMap;
+use device::DeviceDescriptor;
+use error::Error;
+
+/// Gradient of the output of `function` with respect to `input` (which must need gradient), having the same
+/// layout as Value bound to `input`. With `class_index` only given element of every output sample (e.g. class
+/// logit) is differentiated, otherwise sum of all outputs.
+pub fn input_gradient(function: &Function, arguments: &DataMap, input: &Variable, class_index: Option<usize>, device: DeviceDescriptor) -> Value {
+    try_input_gradient(function, arguments, input, class_index, device).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_input_gradient(function: &Function, arguments: &DataMap, input: &Variable, class_index: Option<usize>, device: DeviceDescriptor) -> Result<Value, Error> {
+    let sample_size = match function.outputs().first() {
+        Some(output) => output.shape().try_total_size()?,
+        None => 0,
+    };
+    if let Some(index) = class_index {
+        if index >= sample_size {
+            return Err(Error::new("Class index is out of range of output", "adversarial::input_gradient", &[]));
+        }
+    }
+    let gradients = function.try_forward_and_gradients_with(arguments, &mut DataMap::new(), &[input], device, |output_value| {
+        let root = (0..output_value.shape().try_total_size()?)
+            .map(|i| match class_index {
+                Some(index) if i % sample_size != index => 0f32,
+                _ => 1f32,
+            })
+            .collect::<Vec<_>>();
+        Value::try_from_vec_like(output_value, &root)
+    })?;
+    Ok(gradients[input].clone())
+}
+
+/// Parameters of `fgsm` and `pgd` attacks.
+#[derive(Debug, Clone)]
+pub struct AttackOptions {
+    epsilon: f32,
+    step_size: f32,
+    num_steps: usize,
+    value_range: Option<(f32, f32)>,
+}
+
+impl AttackOptions {
+    /// Attack changing every element by at most `epsilon`, by default one step of size `epsilon` (i.e. FGSM).
+    pub fn new(epsilon: f32) -> AttackOptions {
+        AttackOptions {
+            epsilon,
+            step_size: epsilon,
+            num_steps: 1,
+            value_range: None,
+        }
+    }
+
+    pub fn step_size(mut self, step_size: f32) -> AttackOptions {
+        self.step_size = step_size;
+        self
+    }
+
+    pub fn num_steps(mut self, num_steps: usize) -> AttackOptions {
+        self.num_steps = num_steps;
+        self
+    }
+
+    /// Clips the result to valid input values (e.g. pixel intensities).
+    pub fn value_range(mut self, low: f32, high: f32) -> AttackOptions {
+        self.value_range = Some((low, high));
+        self
+    }
+}
+
+/// Fast gradient sign method: moves Value bound to `input` by `epsilon` in the direction of sign of gradient
+/// of scalar `loss` (step size and number of steps of `options` are ignored).
+pub fn fgsm(loss: &Function, arguments: &DataMap, input: &Variable, options: &AttackOptions, device: DeviceDescriptor) -> Value {
+    try_fgsm(loss, arguments, input, options, device).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_fgsm(loss: &Function, arguments: &DataMap, input: &Variable, options: &AttackOptions, device: DeviceDescriptor) -> Result<Value, Error> {
+    try_pgd(loss, arguments, input, &options.clone().step_size(options.epsilon).num_steps(1), device)
+}
+
+/// Projected gradient descent attack: `num_steps` of FGSM with `step_size`, after each step the Value is
+/// projected back to `epsilon` neighbourhood (in max norm) of the original Value and clipped to value range.
+pub fn pgd(loss: &Function, arguments: &DataMap, input: &Variable, options: &AttackOptions, device: DeviceDescriptor) -> Value {
+    try_pgd(loss, arguments, input, options, device).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_pgd(loss: &Function, arguments: &DataMap, input: &Variable, options: &AttackOptions, device: DeviceDescriptor) -> Result<Value, Error> {
+    let original = arguments.get(input).ok_or_else(|| Error::new("Input is not bound in arguments", "adversarial::pgd", &[input]))?;
+    let original_data = original.try_to_vec()?;
+    let epsilon = options.epsilon;
+    let mut arguments = arguments.clone();
+    let mut adversarial = original.clone();
+    for _ in 0..options.num_steps {
+        let gradient = loss.try_gradients(&arguments, &[input], device)?.remove(input).unwrap();
+        let data = adversarial.try_to_vec()?.iter().zip(gradient.try_to_vec()?).zip(original_data.iter())
+            .map(|((&x, g), &x0)| {
+                let x = (x + options.step_size * sign(g)).max(x0 - epsilon).min(x0 + epsilon);
+                match options.value_range {
+                    Some((low, high)) => x.max(low).min(high),
+                    None => x,
+                }
+            })
+            .collect::<Vec<_>>();
+        adversarial = Value::try_from_vec_like(&original, &data)?;
+        arguments.set(input, &adversarial);
+    }
+    Ok(adversarial)
+}
+
+fn sign(x: f32) -> f32 {
+    if x > 0. {
+        1.
+    } else if x < 0. {
+        -1.
+    } else {
+        0.
+    }
+}
@@ -0,0 +1,116 @@
+use shape::{Shape, ShapeInner};
+use axis::{Axis, AxisInner};
+use nd_array_view::{NDArrayView, NDArrayViewInner};
+use function::take_string;
+use std::collections::HashMap;
+use std::os::raw::c_void;
+
+cpp! {{
+  #include <CNTKLibrary.h>
+  #include <cstdio>
+  #include <vector>
+
+  using namespace CNTK;
+  using namespace std;
+}}
+
+/// Value of function attribute (entry of CNTK `Dictionary`).
+#[derive(Debug, Clone)]
+pub enum AttributeValue {
+    None,
+    Bool(bool),
+    Int(i32),
+    SizeT(usize),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Shape(Shape),
+    Axis(Axis),
+    Vector(Vec<AttributeValue>),
+    Dictionary(HashMap<String, AttributeValue>),
+    NDArrayView(NDArrayView),
+}
+
+/// Converts `const Dictionary*` into map of attributes.
+pub(super) unsafe fn from_dictionary(dict: *const c_void) -> HashMap<String, AttributeValue> {
+    let size = cpp!([dict as "const Dictionary*"] -> usize as "size_t" {
+        return dict->Size();
+    });
+    (0..size).map(|i| {
+        let key = cpp!([dict as "const Dictionary*", i as "size_t"] -> *mut c_void as "string*" {
+            auto it = dict->begin();
+            advance(it, i);
+            return new string(it->first.begin(), it->first.end());
+        });
+        let value = cpp!([dict as "const Dictionary*", i as "size_t"] -> *const c_void as "const DictionaryValue*" {
+            auto it = dict->begin();
+            advance(it, i);
+            return &it->second;
+        });
+        (take_string(key), from_dictionary_value(value))
+    }).collect()
+}
+
+/// Converts `const DictionaryValue*` into AttributeValue (unsupported types become `AttributeValue::None`).
+unsafe fn from_dictionary_value(value: *const c_void) -> AttributeValue {
+    let kind = cpp!([value as "const DictionaryValue*"] -> u32 as "unsigned int" {
+        switch (value->ValueType()) {
+            case DictionaryValue::Type::Bool: return 1;
+            case DictionaryValue::Type::Int: return 2;
+            case DictionaryValue::Type::SizeT: return 3;
+            case DictionaryValue::Type::Float: return 4;
+            case DictionaryValue::Type::Double: return 5;
+            case DictionaryValue::Type::String: return 6;
+            case DictionaryValue::Type::NDShape: return 7;
+            case DictionaryValue::Type::Axis: return 8;
+            case DictionaryValue::Type::Vector: return 9;
+            case DictionaryValue::Type::Dictionary: return 10;
+            case DictionaryValue::Type::NDArrayView: return 11;
+            default: return 0;
+        }
+    });
+    match kind {
+        1 => AttributeValue::Bool(cpp!([value as "const DictionaryValue*"] -> bool as "bool" {
+            return value->Value<bool>();
+        })),
+        2 => AttributeValue::Int(cpp!([value as "const DictionaryValue*"] -> i32 as "int" {
+            return value->Value<int>();
+        })),
+        3 => AttributeValue::SizeT(cpp!([value as "const DictionaryValue*"] -> usize as "size_t" {
+            return value->Value<size_t>();
+        })),
+        4 => AttributeValue::Float(cpp!([value as "const DictionaryValue*"] -> f32 as "float" {
+            return value->Value<float>();
+        })),
+        5 => AttributeValue::Double(cpp!([value as "const DictionaryValue*"] -> f64 as "double" {
+            return value->Value<double>();
+        })),
+        6 => AttributeValue::String(take_string(cpp!([value as "const DictionaryValue*"] -> *mut c_void as "string*" {
+            auto& wvalue = value->Value<wstring>();
+            return new string(wvalue.begin(), wvalue.end());
+        }))),
+        7 => AttributeValue::Shape(Shape { payload: cpp!([value as "const DictionaryValue*"] -> ShapeInner as "NDShape" {
+            return value->Value<NDShape>();
+        })}),
+        8 => AttributeValue::Axis(Axis { payload: cpp!([value as "const DictionaryValue*"] -> AxisInner as "Axis" {
+            return value->Value<Axis>();
+        })}),
+        9 => {
+            let size = cpp!([value as "const DictionaryValue*"] -> usize as "size_t" {
+                return value->Value<vector<DictionaryValue>>().size();
+            });
+            AttributeValue::Vector((0..size).map(|i| {
+                from_dictionary_value(cpp!([value as "const DictionaryValue*", i as "size_t"] -> *const c_void as "const DictionaryValue*" {
+                    return &value->Value<vector<DictionaryValue>>()[i];
+                }))
+            }).collect())
+        },
+        10 => AttributeValue::Dictionary(from_dictionary(cpp!([value as "const DictionaryValue*"] -> *const c_void as "const Dictionary*" {
+            return &value->Value<Dictionary>();
+        }))),
+        11 => AttributeValue::NDArrayView(NDArrayView { payload: cpp!([value as "const DictionaryValue*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+            return value->Value<NDArrayView>().DeepClone();
+        })}),
+        _ => AttributeValue::None,
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 cpp! {{
   #include <CNTKLibrary.h>
   #include <cstdio>
@@ -68,6 +70,101 @@ impl Axis {
             }
         }
     }
+
+    /// Axis past the last static axis (e.g. for slicing to the end)
+    pub fn end_static_axis() -> Axis {
+        Axis {
+            payload: unsafe {
+                cpp!([] -> AxisInner as "Axis" {
+                    return Axis::EndStaticAxis();
+                })
+            }
+        }
+    }
+
+    /// Placeholder for sequence axis of the operand (resolved when function is composed)
+    pub fn operand_sequence_axis() -> Axis {
+        Axis {
+            payload: unsafe {
+                cpp!([] -> AxisInner as "Axis" {
+                    return Axis::OperandSequenceAxis();
+                })
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let payload = self.payload;
+        let name_size = unsafe {
+            cpp!([payload as "Axis"] -> usize as "size_t" {
+                auto wname = payload.Name();
+                string name(wname.begin(), wname.end());
+                return name.size();
+            })
+        };
+        let mut bytes = Vec::with_capacity(name_size);
+        unsafe {
+            bytes.set_len(name_size);
+            let mut ptr = bytes.as_mut_ptr();
+            cpp!([payload as "Axis", mut ptr as "char*"] {
+                auto wname = payload.Name();
+                string name(wname.begin(), wname.end());
+                copy(name.begin(), name.end(), ptr);
+            })
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
+    pub fn is_static(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "Axis"] -> bool as "bool" {
+                return payload.IsStaticAxis();
+            })
+        }
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "Axis"] -> bool as "bool" {
+                return payload.IsDynamicAxis();
+            })
+        }
+    }
+
+    /// Index of static axis, None for dynamic axes
+    pub fn static_index(&self) -> Option<i32> {
+        if !self.is_static() {
+            return None;
+        }
+        let payload = self.payload;
+        Some(unsafe {
+            cpp!([payload as "Axis"] -> i32 as "int" {
+                return payload.StaticAxisIndex(false);
+            })
+        })
+    }
+}
+
+impl PartialEq for Axis {
+    fn eq(&self, other: &Axis) -> bool {
+        let payload = self.payload;
+        let opayload = other.payload;
+        unsafe {
+            cpp!([payload as "Axis", opayload as "Axis"] -> bool as "bool" {
+                return payload == opayload;
+            })
+        }
+    }
+}
+
+impl Eq for Axis {}
+
+impl fmt::Debug for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Axis({})", self.name())
+    }
 }
 
 impl Drop for Axis {
@@ -1,5 +1,5 @@
 use variable::Variable;
-use value::{Value, ValueInner};
+use value::{Value, ValueInner, BorrowedValue};
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -30,7 +30,7 @@ impl DataMap {
         }
     }
 
-    /// Adds binding to DataMap. If mapping for given Variable exists, it will be overwritten.
+    /// Adds binding to DataMap. If mapping for given Variable exists, it is kept (use `set` to replace it).
     pub fn add<T: Into<Variable>>(&mut self, variable: T, value: &Value) {
         let v = variable.into();
         let var_payload = v.payload;
@@ -44,6 +44,35 @@ impl DataMap {
         }
     }
 
+    /// Binds `value` to `variable`, replacing existing binding.
+    pub fn set<T: Into<Variable>>(&mut self, variable: T, value: &Value) {
+        let v = variable.into();
+        let var_payload = v.payload;
+        let val_payload = value.payload;
+        let mut payload = self.payload;
+
+        unsafe {
+            cpp!([mut payload as "unordered_map<Variable, ValuePtr>*", var_payload as "Variable", val_payload as "ValuePtr"] {
+                (*payload)[var_payload] = val_payload;
+            })
+        }
+    }
+
+    /// Binds Value over borrowed data (see `BorrowedValue`), without copying it. Writable borrowed Value
+    /// bound in output DataMap receives results of evaluation directly.
+    pub fn add_borrowed<'a, T: Into<Variable>>(&mut self, variable: T, value: &BorrowedValue<'a>) {
+        let v = variable.into();
+        let var_payload = v.payload;
+        let val_payload = value.payload();
+        let mut payload = self.payload;
+
+        unsafe {
+            cpp!([mut payload as "unordered_map<Variable, ValuePtr>*", var_payload as "Variable", val_payload as "ValuePtr"] {
+                (*payload)[var_payload] = val_payload;
+            })
+        }
+    }
+
     /// Adds binding to null to DataMap. Useful, when we want function evaluation to create the Value.
     pub fn add_null<T: Into<Variable>>(&mut self, variable: T) {
         let v = variable.into();
@@ -72,15 +101,30 @@ impl DataMap {
         };
 
         if has_var {
-            Some(
-                Value { payload: unsafe {
-                    cpp!([payload as "unordered_map<Variable, ValuePtr>*", var_payload as "Variable"] -> ValueInner as "ValuePtr" {
-                        return payload->find(var_payload)->second;
-                    })
-                }}
-            )
-        } else {
-            None
+            // borrowed values are copied, erased ones (borrow has ended) are skipped
+            let value = Value { payload: unsafe {
+                cpp!([payload as "unordered_map<Variable, ValuePtr>*", var_payload as "Variable"] -> ValueInner as "ValuePtr" {
+                    return cntk_rs_owned_value(payload->find(var_payload)->second);
+                })
+            }};
+            if !value.is_null() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+impl Clone for DataMap {
+    /// Copies the bindings (Values are shared)
+    fn clone(&self) -> Self {
+        let xpayload = self.payload;
+        DataMap {
+            payload: unsafe {
+                cpp!([xpayload as "unordered_map<Variable, ValuePtr>*"] -> *mut DataMapInner as "unordered_map<Variable, ValuePtr>*" {
+                    return new unordered_map<Variable, ValuePtr>(*xpayload);
+                })
+            }
         }
     }
 }
@@ -0,0 +1,41 @@
+/// Element type of variables and values (mirrors CNTK DataType enum)
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Unknown = 0,
+    Float = 1,
+    Double = 2,
+}
+
+impl DataType {
+    pub(super) fn from_raw(raw: u32) -> DataType {
+        match raw {
+            1 => DataType::Float,
+            2 => DataType::Double,
+            _ => DataType::Unknown,
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// Rust element type which can be passed into CNTK. Implemented for f32 (float) and f64 (double).
+pub trait CntkElement: Copy + Default + Into<f64> + private::Sealed + 'static {
+    fn data_type() -> DataType;
+}
+
+impl CntkElement for f32 {
+    fn data_type() -> DataType {
+        DataType::Float
+    }
+}
+
+impl CntkElement for f64 {
+    fn data_type() -> DataType {
+        DataType::Double
+    }
+}
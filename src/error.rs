@@ -0,0 +1,97 @@
+use variable::Variable;
+use std::fmt;
+use std::error;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+cpp! {{
+  #include <CNTKLibrary.h>
+  #include <cstring>
+  #include <exception>
+
+  using namespace CNTK;
+  using namespace std;
+
+  // Message is released by check_error on the Rust side.
+  static char* cntk_rs_error_message(const char* what) {
+      char* message = new char[strlen(what)+1];
+      strcpy(message, what);
+      return message;
+  }
+
+  // Shared exception bridge: runs f and stores message of any exception thrown into error_p,
+  // so that no C++ exception unwinds across FFI boundary.
+  template <typename F>
+  static auto cntk_rs_guard(char*& error_p, F f) -> decltype(f()) {
+      try {
+          return f();
+      } catch (std::exception& e) {
+          error_p = cntk_rs_error_message(e.what());
+      } catch (...) {
+          error_p = cntk_rs_error_message("Unknown C++ exception");
+      }
+      return decltype(f())();
+  }
+}}
+
+/// Error raised by CNTK (or by the wrapper while validating arguments) during a fallible call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    message: String,
+    call: &'static str,
+    variables: Vec<String>,
+}
+
+impl Error {
+    pub(super) fn new(message: &str, call: &'static str, variables: &[&Variable]) -> Error {
+        Error {
+            message: message.to_owned(),
+            call,
+            variables: variables.iter().map(|x| x.name()).filter(|x| !x.is_empty()).collect(),
+        }
+    }
+
+    /// Message reported by CNTK
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Name of the failing call (CNTK function or wrapper method)
+    pub fn call(&self) -> &'static str {
+        self.call
+    }
+
+    /// Names of the (named) variables involved in the failing call
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.call, self.message)?;
+        if !self.variables.is_empty() {
+            write!(f, " (variables: {})", self.variables.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Turns error message set by cntk_rs_guard into Error and releases the message.
+pub(super) unsafe fn check_error(error_p: *mut c_char, call: &'static str, variables: &[&Variable]) -> Result<(), Error> {
+    if error_p.is_null() {
+        Ok(())
+    } else {
+        let err = Error::new(&CStr::from_ptr(error_p).to_string_lossy(), call, variables);
+        cpp!([error_p as "char*"] {
+            delete[] error_p;
+        });
+        Err(err)
+    }
+}
@@ -1,10 +1,17 @@
-use variable::{Variable, VariableInner};
+use variable::{Variable, VariableInner, Parameter};
 use variable_set::VariableSet;
 use data_map::DataMap;
 use replacement_map::ReplacementMap;
 use device::DeviceDescriptor;
+use value::Value;
+use data_type::DataType;
+use std::borrow::Borrow;
 use std::ptr;
-use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::collections::{HashMap, HashSet};
+use std::slice;
+use attribute::{AttributeValue, from_dictionary};
+use error::{Error, check_error};
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -24,6 +31,25 @@ pub struct Function {
     pub(super) payload: FunctionInner
 }
 
+/// How parameters are treated by `Function::deep_clone`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterCloningMethod {
+    /// Clone shares parameters with the original function
+    Share = 0,
+    /// Clone gets its own copies of parameters (with current values)
+    Clone = 1,
+    /// Parameters are replaced by constants with current values
+    Freeze = 2,
+}
+
+/// Node of the graph passed to `Function::visit`.
+#[derive(Debug)]
+pub enum GraphNode {
+    /// Primitive (or block) function
+    Function(Function),
+    Variable(Variable),
+}
+
 pub struct BackPropState {
     payload: BackPropStateInner
 }
@@ -39,15 +65,62 @@ impl Function {
         }}
     }
 
-    pub fn combine(variables: &[&Variable]) -> Function {
-        let data: Vec<Variable> = variables.iter().map(|&x| x.clone()).collect();
+    pub fn combine<T: Borrow<Variable>>(variables: &[T]) -> Function {
+        Function::try_combine(variables).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_combine<T: Borrow<Variable>>(variables: &[T]) -> Result<Function, Error> {
+        let data: Vec<Variable> = variables.iter().map(|x| x.borrow().clone()).collect();
         let data_ptr = data.as_ptr();
         let data_size = data.len();
-        Function { payload: unsafe {
-            cpp!([data_ptr as "Variable*", data_size as "size_t"] -> FunctionInner as "FunctionPtr" {
-                return Combine(vector<Variable>(data_ptr, data_ptr + data_size));
-            })
-        }}
+        Ok(Function { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([data_ptr as "Variable*", data_size as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+                return cntk_rs_guard(error_p, [&] { return Combine(vector<Variable>(data_ptr, data_ptr + data_size)); });
+            });
+            check_error(error_p, "Combine", &data.iter().collect::<Vec<_>>())?;
+            payload
+        }})
+    }
+
+    /// Encapsulates `composite` into block function, which is shown as single node with `op_name` and `block_name`.
+    /// Every placeholder of `composite` must be mapped to actual argument of the block in `argument_map`.
+    pub fn as_block<P: Borrow<Variable>, A: Borrow<Variable>>(composite: &Function, argument_map: &[(P, A)], op_name: &str, block_name: &str) -> Function {
+        Function::try_as_block(composite, argument_map, op_name, block_name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_as_block<P: Borrow<Variable>, A: Borrow<Variable>>(composite: &Function, argument_map: &[(P, A)], op_name: &str, block_name: &str) -> Result<Function, Error> {
+        let payload = composite.payload;
+        let placeholders: Vec<Variable> = argument_map.iter().map(|x| x.0.borrow().clone()).collect();
+        let arguments: Vec<Variable> = argument_map.iter().map(|x| x.1.borrow().clone()).collect();
+        let placeholders_ptr = placeholders.as_ptr();
+        let arguments_ptr = arguments.as_ptr();
+        let map_size = argument_map.len();
+        let op_name_ptr = op_name.as_ptr();
+        let op_name_len = op_name.len();
+        let block_name_ptr = block_name.as_ptr();
+        let block_name_len = block_name.len();
+        Ok(Function { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "FunctionPtr", placeholders_ptr as "Variable*", arguments_ptr as "Variable*", map_size as "size_t",
+                                op_name_ptr as "char*", op_name_len as "size_t", block_name_ptr as "char*", block_name_len as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+                return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                    vector<pair<Variable, Variable>> argument_map;
+                    for (size_t i = 0; i < map_size; i++) {
+                        argument_map.push_back({placeholders_ptr[i], arguments_ptr[i]});
+                    }
+                    string op_name(op_name_ptr, op_name_ptr + op_name_len);
+                    wstring wop_name;
+                    wop_name.assign(op_name.begin(), op_name.end());
+                    string block_name(block_name_ptr, block_name_ptr + block_name_len);
+                    wstring wblock_name;
+                    wblock_name.assign(block_name.begin(), block_name.end());
+                    return AsBlock(FunctionPtr(payload), argument_map, wop_name, wblock_name);
+                });
+            });
+            check_error(error_p, "AsBlock", &arguments.iter().collect::<Vec<_>>())?;
+            payload
+        }})
     }
 
     pub fn num_outputs(&self) -> usize {
@@ -60,144 +133,199 @@ impl Function {
     }
 
     pub fn to_variable(&self) -> Result<Variable, &'static str> {
-        let payload = self.payload;
         if self.num_outputs() > 1 {
             Err("Cannot convert function with multiple outputs into Variable")
         } else {
-            Ok(Variable { payload: unsafe {
-                cpp!([payload as "FunctionPtr"] -> VariableInner as "Variable" {
-                    return Variable(payload);
-                })
-            }})
+            Ok(self.try_to_variable().unwrap_or_else(|e| panic!("{}", e)))
         }
     }
 
+    pub fn try_to_variable(&self) -> Result<Variable, Error> {
+        let payload = self.payload;
+        if self.num_outputs() > 1 {
+            return Err(Error::new("Cannot convert function with multiple outputs into Variable", "Variable::Variable", &[]));
+        }
+        Ok(Variable { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "FunctionPtr", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&] { return Variable(payload); });
+            });
+            check_error(error_p, "Variable::Variable", &[])?;
+            payload
+        }})
+    }
+
     pub fn evaluate(&self, input_data_map: &DataMap, output_data_map: &mut DataMap, device: DeviceDescriptor) {
+        self.try_evaluate(input_data_map, output_data_map, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_evaluate(&self, input_data_map: &DataMap, output_data_map: &mut DataMap, device: DeviceDescriptor) -> Result<(), Error> {
         let payload = self.payload;
         let impayload = input_data_map.payload;
         let mut ompayload = output_data_map.payload;
         let dpayload = device.payload;
         unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+            let mut error_p: *mut c_char = ptr::null_mut();
             cpp!([payload as "FunctionPtr", impayload as "unordered_map<Variable, ValuePtr>*", mut ompayload as "unordered_map<Variable, ValuePtr>*", dpayload as "DeviceDescriptor", mut error_p as "char*"] {
-                try {
-                    payload->Evaluate(*impayload, *ompayload, dpayload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                }
+                cntk_rs_guard(error_p, [&] { payload->Evaluate(*impayload, *ompayload, dpayload); });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
-        };
+            check_error(error_p, "Function::Evaluate", &[])
+        }
     }
 
     pub fn forward(&self, input_data_map: &DataMap, output_data_map: &mut DataMap, device: DeviceDescriptor,
                    retain_backward_state_for: &VariableSet, exclude_gradients_for: &VariableSet) -> BackPropState {
+        self.try_forward(input_data_map, output_data_map, device, retain_backward_state_for, exclude_gradients_for).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_forward(&self, input_data_map: &DataMap, output_data_map: &mut DataMap, device: DeviceDescriptor,
+                       retain_backward_state_for: &VariableSet, exclude_gradients_for: &VariableSet) -> Result<BackPropState, Error> {
         let payload = self.payload;
         let impayload = input_data_map.payload;
         let mut ompayload = output_data_map.payload;
         let dpayload = device.payload;
         let bspayload = retain_backward_state_for.payload;
         let egpayload = exclude_gradients_for.payload;
-        BackPropState { payload: unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+        Ok(BackPropState { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
             let payload = cpp!([payload as "FunctionPtr", impayload as "unordered_map<Variable, ValuePtr>*", mut ompayload as "unordered_map<Variable, ValuePtr>*", dpayload as "DeviceDescriptor",
                   bspayload as "unordered_set<Variable>*", egpayload as "unordered_set<Variable>*", mut error_p as "char*"] -> BackPropStateInner as "BackPropStatePtr" {
-                try {
-                    return payload->Forward(*impayload, *ompayload, dpayload, *bspayload, *egpayload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+                return cntk_rs_guard(error_p, [&] { return payload->Forward(*impayload, *ompayload, dpayload, *bspayload, *egpayload); });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Function::Forward", &[])?;
             payload
-        }}
+        }})
     }
 
     pub fn backward(&self, bpstate: &BackPropState, gradient_values: &DataMap, output_map: &mut DataMap) {
+        self.try_backward(bpstate, gradient_values, output_map).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_backward(&self, bpstate: &BackPropState, gradient_values: &DataMap, output_map: &mut DataMap) -> Result<(), Error> {
         let payload = self.payload;
         let bppayload = bpstate.payload;
         let gpayload = gradient_values.payload;
         let mut opayload = output_map.payload;
         // TODO: check if requested variables allow gradients
         unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+            let mut error_p: *mut c_char = ptr::null_mut();
             cpp!([payload as "FunctionPtr", bppayload as "BackPropStatePtr", gpayload as "unordered_map<Variable, ValuePtr>*", mut opayload as "unordered_map<Variable, ValuePtr>*", mut error_p as "char*"] {
-                try {
-                    payload->Backward(bppayload, *gpayload, *opayload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                }
+                cntk_rs_guard(error_p, [&] { payload->Backward(bppayload, *gpayload, *opayload); });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
+            check_error(error_p, "Function::Backward", &[])
+        }
+    }
+
+    /// Computes gradients of the (single, scalar) output with respect to `wrt` (inputs needing gradient or parameters).
+    /// Fails if gradient of any of `wrt` was not computed.
+    pub fn gradients<W: Borrow<Variable>>(&self, arguments: &DataMap, wrt: &[W], device: DeviceDescriptor) -> HashMap<Variable, Value> {
+        self.try_gradients(arguments, wrt, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_gradients<W: Borrow<Variable>>(&self, arguments: &DataMap, wrt: &[W], device: DeviceDescriptor) -> Result<HashMap<Variable, Value>, Error> {
+        self.try_forward_and_gradients(arguments, &mut DataMap::new(), wrt, None, device)
+    }
+
+    /// Evaluates the function (filling `outputs`, output of the function is always added) and computes gradients
+    /// with respect to `wrt`. Gradient of the output is set to ones if `root_gradient` is None, which is only
+    /// allowed for scalar outputs.
+    pub fn forward_and_gradients<W: Borrow<Variable>>(&self, arguments: &DataMap, outputs: &mut DataMap, wrt: &[W],
+                                                      root_gradient: Option<&Value>, device: DeviceDescriptor) -> HashMap<Variable, Value> {
+        self.try_forward_and_gradients(arguments, outputs, wrt, root_gradient, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_forward_and_gradients<W: Borrow<Variable>>(&self, arguments: &DataMap, outputs: &mut DataMap, wrt: &[W],
+                                                          root_gradient: Option<&Value>, device: DeviceDescriptor) -> Result<HashMap<Variable, Value>, Error> {
+        if root_gradient.is_none() {
+            for output in self.outputs() {
+                if output.shape().try_total_size()? != 1 {
+                    return Err(Error::new("Root gradient must be supplied for function with non-scalar output", "Function::Gradients", &[]));
+                }
             }
-        };
+        }
+        self.try_forward_and_gradients_with(arguments, outputs, wrt, device, |output_value| match root_gradient {
+            Some(value) => Ok(value.clone()),
+            None => {
+                let size = output_value.shape().try_total_size()?;
+                match output_value.data_type() {
+                    DataType::Double => Value::try_from_vec_like_of(output_value, &vec![1f64; size]),
+                    _ => Value::try_from_vec_like(output_value, &vec![1f32; size]),
+                }
+            }
+        })
+    }
+
+    /// Like `try_forward_and_gradients`, gradient of the output is created from its value by `root_gradient`.
+    pub(super) fn try_forward_and_gradients_with<W, F>(&self, arguments: &DataMap, outputs: &mut DataMap, wrt: &[W],
+                                                       device: DeviceDescriptor, root_gradient: F) -> Result<HashMap<Variable, Value>, Error>
+        where W: Borrow<Variable>, F: FnOnce(&Value) -> Result<Value, Error> {
+        let output = self.to_variable().map_err(|e| Error::new(e, "Function::Gradients", &[]))?;
+
+        outputs.add_null(&output);
+        let mut retain_state = VariableSet::new();
+        retain_state.add(&output);
+        let bpstate = self.try_forward(arguments, outputs, device, &retain_state, &VariableSet::new())?;
+
+        let mut root_gradients = DataMap::new();
+        root_gradients.add(&output, &root_gradient(&outputs.get(&output).unwrap())?);
+
+        let mut result = DataMap::new();
+        for variable in wrt {
+            result.add_null(variable.borrow());
+        }
+        self.try_backward(&bpstate, &root_gradients, &mut result)?;
+
+        wrt.iter().map(|x| {
+            let variable = x.borrow();
+            result.get(variable)
+                .map(|value| (variable.clone(), value))
+                .ok_or_else(|| Error::new("Gradient was not computed (variable must need gradient)", "Function::Gradients", &[variable]))
+        }).collect()
     }
 
     pub fn save(&self, path: &str) {
+        self.try_save(path).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_save(&self, path: &str) -> Result<(), Error> {
         let path_ptr = path.as_ptr();
         let path_len = path.len();
         let payload = self.payload;
         unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+            let mut error_p: *mut c_char = ptr::null_mut();
             cpp!([payload as "FunctionPtr", path_ptr as "char*", path_len as "size_t", mut error_p as "char*"] {
-                try {
+                cntk_rs_guard(error_p, [&] {
                     string path(path_ptr, path_ptr + path_len);
                     wstring wpath;
                     wpath.assign(path.begin(), path.end());
                     payload->Save(wpath);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                }
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Function::Save", &[])
         }
     }
 
     pub fn load(path: &str, device: DeviceDescriptor) -> Function {
+        Function::try_load(path, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_load(path: &str, device: DeviceDescriptor) -> Result<Function, Error> {
         let path_ptr = path.as_ptr();
         let path_len = path.len();
         let dpayload = device.payload;
-        Function {payload: unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+        Ok(Function {payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
             let payload = cpp!([path_ptr as "char*", path_len as "size_t", dpayload as "DeviceDescriptor", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-                try {
+                return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
                     string path(path_ptr, path_ptr + path_len);
                     wstring wpath;
                     wpath.assign(path.begin(), path.end());
                     return Function::Load(wpath, dpayload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Function::Load", &[])?;
             payload
-        }}
+        }})
     }
 
     pub fn num_inputs(&self) -> usize {
@@ -244,26 +372,60 @@ impl Function {
     }
 
     pub fn replace_placeholders(self, placeholder_replacements: &ReplacementMap) -> Function {
+        self.try_replace_placeholders(placeholder_replacements).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_replace_placeholders(self, placeholder_replacements: &ReplacementMap) -> Result<Function, Error> {
         let payload = self.payload;
         let repl_payload = placeholder_replacements.payload;
-        Function {payload: unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+        Ok(Function {payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
             let payload = cpp!([payload as "FunctionPtr", repl_payload as "unordered_map<Variable, Variable>*", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-                try {
-                    return payload->ReplacePlaceholders(*repl_payload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+                return cntk_rs_guard(error_p, [&] { return payload->ReplacePlaceholders(*repl_payload); });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Function::ReplacePlaceholders", &[])?;
             payload
-        }}
+        }})
+    }
+
+    /// Copies the graph of the function.
+    pub fn deep_clone(&self, method: ParameterCloningMethod) -> Function {
+        self.try_deep_clone(method).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_deep_clone(&self, method: ParameterCloningMethod) -> Result<Function, Error> {
+        self.try_deep_clone_with_replacements::<&Variable, &Variable>(method, &[])
+    }
+
+    /// Copies the graph of the function, substituting any Variables (not just placeholders) given in `replacements`
+    /// (e.g. to attach the copy to a new input).
+    pub fn deep_clone_with_replacements<V: Borrow<Variable>, R: Borrow<Variable>>(&self, method: ParameterCloningMethod, replacements: &[(V, R)]) -> Function {
+        self.try_deep_clone_with_replacements(method, replacements).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_deep_clone_with_replacements<V: Borrow<Variable>, R: Borrow<Variable>>(&self, method: ParameterCloningMethod, replacements: &[(V, R)]) -> Result<Function, Error> {
+        let payload = self.payload;
+        let method = method as u32;
+        let variables: Vec<Variable> = replacements.iter().map(|x| x.0.borrow().clone()).collect();
+        let substitutes: Vec<Variable> = replacements.iter().map(|x| x.1.borrow().clone()).collect();
+        let variables_ptr = variables.as_ptr();
+        let substitutes_ptr = substitutes.as_ptr();
+        let map_size = replacements.len();
+        Ok(Function {payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "FunctionPtr", method as "unsigned int", variables_ptr as "Variable*", substitutes_ptr as "Variable*",
+                                map_size as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+                return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                    unordered_map<Variable, Variable> replacements;
+                    for (size_t i = 0; i < map_size; i++) {
+                        replacements.insert({variables_ptr[i], substitutes_ptr[i]});
+                    }
+                    return payload->Clone((ParameterCloningMethod)method, replacements);
+                });
+            });
+            check_error(error_p, "Function::Clone", &variables.iter().collect::<Vec<_>>())?;
+            payload
+        }})
     }
 
     pub fn num_parameters(&self) -> usize {
@@ -275,7 +437,7 @@ impl Function {
         }
     }
 
-    pub fn parameters(&self) -> Vec<Variable> {
+    pub fn parameters(&self) -> Vec<Parameter> {
         let payload = self.payload;
         let num_parameters = self.num_parameters();
         let mut output: Vec<VariableInner> = Vec::with_capacity(num_parameters);
@@ -289,7 +451,255 @@ impl Function {
                 }
             })
         }
-        output.into_iter().map(|x| Variable {payload: x}).collect::<Vec<Variable>>()
+        output.into_iter().map(|x| Parameter::from_variable(Variable {payload: x}).unwrap()).collect::<Vec<Parameter>>()
+    }
+
+    pub fn name(&self) -> String {
+        let payload = self.payload;
+        unsafe {
+            take_string(cpp!([payload as "FunctionPtr"] -> *mut c_void as "string*" {
+                auto& wname = payload->Name();
+                return new string(wname.begin(), wname.end());
+            }))
+        }
+    }
+
+    /// Sets name of the function. CNTK refuses to rename already named function.
+    pub fn set_name(&self, name: &str) {
+        self.try_set_name(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_set_name(&self, name: &str) -> Result<(), Error> {
+        let payload = self.payload;
+        let name_ptr = name.as_ptr();
+        let name_len = name.len();
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            cpp!([payload as "FunctionPtr", name_ptr as "char*", name_len as "size_t", mut error_p as "char*"] {
+                cntk_rs_guard(error_p, [&] {
+                    string name(name_ptr, name_ptr + name_len);
+                    wstring wname;
+                    wname.assign(name.begin(), name.end());
+                    payload->SetName(wname);
+                });
+            });
+            check_error(error_p, "Function::SetName", &[])
+        }
+    }
+
+    pub fn uid(&self) -> String {
+        let payload = self.payload;
+        unsafe {
+            take_string(cpp!([payload as "FunctionPtr"] -> *mut c_void as "string*" {
+                auto& wuid = payload->Uid();
+                return new string(wuid.begin(), wuid.end());
+            }))
+        }
+    }
+
+    /// Name of the operation (e.g. "Plus", "Times"), composite functions report "CompositeFunctionOpName".
+    pub fn op_name(&self) -> String {
+        let payload = self.payload;
+        unsafe {
+            take_string(cpp!([payload as "FunctionPtr"] -> *mut c_void as "string*" {
+                auto& wop_name = payload->OpName();
+                return new string(wop_name.begin(), wop_name.end());
+            }))
+        }
+    }
+
+    /// Attributes of the operation (e.g. axis of reduction), mostly empty for composite functions.
+    pub fn attributes(&self) -> HashMap<String, AttributeValue> {
+        let payload = self.payload;
+        unsafe {
+            from_dictionary(cpp!([payload as "FunctionPtr"] -> *const c_void as "const Dictionary*" {
+                return &payload->Attributes();
+            }))
+        }
+    }
+
+    /// Primitive function producing output of this (composite) function.
+    pub fn root_function(&self) -> Function {
+        let payload = self.payload;
+        Function { payload: unsafe {
+            cpp!([payload as "FunctionPtr"] -> FunctionInner as "FunctionPtr" {
+                return payload->RootFunction();
+            })
+        }}
+    }
+
+    fn is_null(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "FunctionPtr"] -> bool as "bool" {
+                return payload == nullptr;
+            })
+        }
+    }
+
+    pub fn is_composite(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "FunctionPtr"] -> bool as "bool" {
+                return payload->IsComposite();
+            })
+        }
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "FunctionPtr"] -> bool as "bool" {
+                return payload->IsPrimitive();
+            })
+        }
+    }
+
+    pub fn is_block(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "FunctionPtr"] -> bool as "bool" {
+                return payload->IsBlock();
+            })
+        }
+    }
+
+    /// Pairs of (placeholder of block root, actual argument) of block function, empty for other functions.
+    pub fn block_arguments_mapping(&self) -> Vec<(Variable, Variable)> {
+        if !self.is_block() {
+            return Vec::new();
+        }
+        let payload = self.payload;
+        let num_arguments = unsafe {
+            cpp!([payload as "FunctionPtr"] -> usize as "size_t" {
+                return payload->BlockArgumentsMapping().size();
+            })
+        };
+        let mut placeholders: Vec<VariableInner> = Vec::with_capacity(num_arguments);
+        let mut arguments: Vec<VariableInner> = Vec::with_capacity(num_arguments);
+        unsafe {
+            placeholders.set_len(num_arguments);
+            arguments.set_len(num_arguments);
+            let mut placeholders_ptr = placeholders.as_mut_ptr();
+            let mut arguments_ptr = arguments.as_mut_ptr();
+            cpp!([payload as "FunctionPtr", mut placeholders_ptr as "Variable*", mut arguments_ptr as "Variable*"] {
+                auto mapping = payload->BlockArgumentsMapping();
+                for (size_t i = 0; i < mapping.size(); i++) {
+                    ::new (&placeholders_ptr[i]) Variable(mapping[i].first);
+                    ::new (&arguments_ptr[i]) Variable(mapping[i].second);
+                }
+            })
+        }
+        placeholders.into_iter().zip(arguments).map(|(p, a)| (Variable {payload: p}, Variable {payload: a})).collect()
+    }
+
+    /// Finds primitive function with given name (not searching inside block functions).
+    /// Fails if there are more functions with such name.
+    pub fn find_by_name(&self, name: &str) -> Option<Function> {
+        self.try_find_by_name(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_find_by_name(&self, name: &str) -> Result<Option<Function>, Error> {
+        let payload = self.payload;
+        let name_ptr = name.as_ptr();
+        let name_len = name.len();
+        let found = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "FunctionPtr", name_ptr as "char*", name_len as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+                return cntk_rs_guard(error_p, [&] {
+                    string name(name_ptr, name_ptr + name_len);
+                    wstring wname;
+                    wname.assign(name.begin(), name.end());
+                    return payload->FindByName(wname);
+                });
+            });
+            check_error(error_p, "Function::FindByName", &[])?;
+            Function { payload }
+        };
+        if found.is_null() { Ok(None) } else { Ok(Some(found)) }
+    }
+
+    /// Finds all primitive functions with given name (not searching inside block functions).
+    pub fn find_all_by_name(&self, name: &str) -> Vec<Function> {
+        self.try_find_all_by_name(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_find_all_by_name(&self, name: &str) -> Result<Vec<Function>, Error> {
+        let payload = self.payload;
+        let name_ptr = name.as_ptr();
+        let name_len = name.len();
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let found = cpp!([payload as "FunctionPtr", name_ptr as "char*", name_len as "size_t", mut error_p as "char*"] -> *mut c_void as "vector<FunctionPtr>*" {
+                return cntk_rs_guard(error_p, [&] {
+                    string name(name_ptr, name_ptr + name_len);
+                    wstring wname;
+                    wname.assign(name.begin(), name.end());
+                    return new vector<FunctionPtr>(payload->FindAllWithName(wname));
+                });
+            });
+            check_error(error_p, "Function::FindAllWithName", &[])?;
+            let num_found = cpp!([found as "vector<FunctionPtr>*"] -> usize as "size_t" {
+                return found->size();
+            });
+            let mut output: Vec<FunctionInner> = Vec::with_capacity(num_found);
+            output.set_len(num_found);
+            let mut ptr = output.as_mut_ptr();
+            cpp!([found as "vector<FunctionPtr>*", mut ptr as "FunctionPtr*"] {
+                for (size_t i = 0; i < found->size(); i++) {
+                    ::new (&ptr[i]) FunctionPtr((*found)[i]);
+                }
+                delete found;
+            });
+            Ok(output.into_iter().map(|x| Function {payload: x}).collect::<Vec<Function>>())
+        }
+    }
+
+    /// Depth-first (preorder) traversal of the graph starting at the root function. Every primitive function
+    /// is visited once and followed by its inputs, each variable is visited once. Block functions are
+    /// visited as single nodes (use `block_root` to traverse them).
+    pub fn visit<F: FnMut(&GraphNode)>(&self, mut visitor: F) {
+        let mut visited_functions = HashSet::new();
+        let mut visited_variables = HashSet::new();
+        let mut stack = vec!(self.root_function());
+        while let Some(function) = stack.pop() {
+            if !visited_functions.insert(function.uid()) {
+                continue;
+            }
+            let inputs = function.inputs();
+            visitor(&GraphNode::Function(function));
+            for input in inputs.iter() {
+                if visited_variables.insert(input.clone()) {
+                    visitor(&GraphNode::Variable(input.clone()));
+                }
+            }
+            stack.extend(inputs.iter().rev().filter_map(|x| x.owner()));
+        }
+    }
+
+    /// Composite function encapsulated by block function, None for other functions.
+    pub fn block_root(&self) -> Option<Function> {
+        if !self.is_block() {
+            return None;
+        }
+        let payload = self.payload;
+        Some(Function { payload: unsafe {
+            cpp!([payload as "FunctionPtr"] -> FunctionInner as "FunctionPtr" {
+                return payload->BlockRoot();
+            })
+        }})
+    }
+}
+
+impl Clone for Function {
+    fn clone(&self) -> Self {
+        let xpayload = self.payload;
+        let payload = unsafe {
+            cpp!([xpayload as "FunctionPtr"] -> FunctionInner as "FunctionPtr" {
+                return xpayload;
+            })
+        };
+        Function {payload}
     }
 }
 
@@ -313,4 +723,19 @@ impl Drop for BackPropState {
             })
         };
     }
-}
\ No newline at end of file
+}
+
+/// Takes ownership of `string*` allocated on C++ side and converts it into String.
+pub(super) unsafe fn take_string(s: *mut c_void) -> String {
+    let data = cpp!([s as "string*"] -> *const u8 as "const char*" {
+        return s->data();
+    });
+    let len = cpp!([s as "string*"] -> usize as "size_t" {
+        return s->size();
+    });
+    let result = String::from_utf8(slice::from_raw_parts(data, len).to_vec()).unwrap();
+    cpp!([s as "string*"] {
+        delete s;
+    });
+    result
+}
@@ -1,5 +1,8 @@
-use variable::{Variable};
+use variable::{Variable, Parameter};
+use error::{Error, check_error};
 use std::borrow::Borrow;
+use std::ptr;
+use std::os::raw::c_char;
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -45,48 +48,63 @@ pub struct Learner {
 }
 
 impl Learner {
-    pub fn sgd<T: Borrow<Variable>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule) -> Learner {
-        check_parameters(parameters);
+    pub fn sgd<T: Borrow<Parameter>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule) -> Learner {
+        Learner::try_sgd(parameters, learning_rate_schedule).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        let data: Vec<Variable> = parameters.iter().map(|x| x.borrow().clone()).collect();
+    pub fn try_sgd<T: Borrow<Parameter>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule) -> Result<Learner, Error> {
+        let data: Vec<Variable> = parameters.iter().map(|x| x.borrow().to_variable()).collect();
         let data_ptr = data.as_ptr();
         let data_size = data.len();
         let schedule = learning_rate_schedule.payload;
-        Learner { payload: unsafe {
-            cpp!([data_ptr as "Parameter*", data_size as "size_t", schedule as "TrainingParameterSchedule<double>"] -> LearnerInner as "LearnerPtr" {
-                return SGDLearner(vector<Parameter>(data_ptr, data_ptr + data_size), schedule);
-            })
-        }}
+        Ok(Learner { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([data_ptr as "Parameter*", data_size as "size_t", schedule as "TrainingParameterSchedule<double>", mut error_p as "char*"] -> LearnerInner as "LearnerPtr" {
+                return cntk_rs_guard(error_p, [&] { return SGDLearner(vector<Parameter>(data_ptr, data_ptr + data_size), schedule); });
+            });
+            check_error(error_p, "SGDLearner", &[])?;
+            payload
+        }})
     }
 
-    pub fn momentum_sgd<T: Borrow<Variable>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule, momentum_schedule: &DoubleParameterSchedule) -> Learner {
-        check_parameters(parameters);
+    pub fn momentum_sgd<T: Borrow<Parameter>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule, momentum_schedule: &DoubleParameterSchedule) -> Learner {
+        Learner::try_momentum_sgd(parameters, learning_rate_schedule, momentum_schedule).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        let data: Vec<Variable> = parameters.iter().map(|x| x.borrow().clone()).collect();
+    pub fn try_momentum_sgd<T: Borrow<Parameter>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule, momentum_schedule: &DoubleParameterSchedule) -> Result<Learner, Error> {
+        let data: Vec<Variable> = parameters.iter().map(|x| x.borrow().to_variable()).collect();
         let data_ptr = data.as_ptr();
         let data_size = data.len();
         let schedule = learning_rate_schedule.payload;
         let mschedule = momentum_schedule.payload;
-        Learner { payload: unsafe {
-            cpp!([data_ptr as "Parameter*", data_size as "size_t", schedule as "TrainingParameterSchedule<double>", mschedule as "TrainingParameterSchedule<double>"] -> LearnerInner as "LearnerPtr" {
-                return MomentumSGDLearner(vector<Parameter>(data_ptr, data_ptr + data_size), schedule, mschedule);
-            })
-        }}
+        Ok(Learner { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([data_ptr as "Parameter*", data_size as "size_t", schedule as "TrainingParameterSchedule<double>", mschedule as "TrainingParameterSchedule<double>", mut error_p as "char*"] -> LearnerInner as "LearnerPtr" {
+                return cntk_rs_guard(error_p, [&] { return MomentumSGDLearner(vector<Parameter>(data_ptr, data_ptr + data_size), schedule, mschedule); });
+            });
+            check_error(error_p, "MomentumSGDLearner", &[])?;
+            payload
+        }})
     }
 
-    pub fn adam<T: Borrow<Variable>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule, momentum_schedule: &DoubleParameterSchedule) -> Learner {
-        check_parameters(parameters);
+    pub fn adam<T: Borrow<Parameter>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule, momentum_schedule: &DoubleParameterSchedule) -> Learner {
+        Learner::try_adam(parameters, learning_rate_schedule, momentum_schedule).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        let data: Vec<Variable> = parameters.iter().map(|x| x.borrow().clone()).collect();
+    pub fn try_adam<T: Borrow<Parameter>>(parameters: &[T], learning_rate_schedule: &DoubleParameterSchedule, momentum_schedule: &DoubleParameterSchedule) -> Result<Learner, Error> {
+        let data: Vec<Variable> = parameters.iter().map(|x| x.borrow().to_variable()).collect();
         let data_ptr = data.as_ptr();
         let data_size = data.len();
         let schedule = learning_rate_schedule.payload;
         let mschedule = momentum_schedule.payload;
-        Learner { payload: unsafe {
-            cpp!([data_ptr as "Parameter*", data_size as "size_t", schedule as "TrainingParameterSchedule<double>", mschedule as "TrainingParameterSchedule<double>"] -> LearnerInner as "LearnerPtr" {
-                return AdamLearner(vector<Parameter>(data_ptr, data_ptr + data_size), schedule, mschedule);
-            })
-        }}
+        Ok(Learner { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([data_ptr as "Parameter*", data_size as "size_t", schedule as "TrainingParameterSchedule<double>", mschedule as "TrainingParameterSchedule<double>", mut error_p as "char*"] -> LearnerInner as "LearnerPtr" {
+                return cntk_rs_guard(error_p, [&] { return AdamLearner(vector<Parameter>(data_ptr, data_ptr + data_size), schedule, mschedule); });
+            });
+            check_error(error_p, "AdamLearner", &[])?;
+            payload
+        }})
     }
 }
 
@@ -100,9 +118,3 @@ impl Drop for Learner {
         };
     }
 }
-
-fn check_parameters<T: Borrow<Variable>>(parameters: &[T]) {
-    for parameter in parameters {
-        assert!(parameter.borrow().is_parameter());
-    }
-}
\ No newline at end of file
@@ -43,13 +43,35 @@
 //! In C++ there is automatic conversion between variable and function (although converting a
 //! function to variable will fail, if function has more than one output). We also allow this
 //! conversion in Rust wrapper to improve ergonomics of function composition and other things.
+//!
+//! ## Error handling
+//!
+//! Exceptions thrown by CNTK are turned into Rust panics. Every fallible call also has `try_`
+//! variant (e.g. `ops::try_times`, `Function::try_evaluate`, `Value::try_batch_from_vec`),
+//! which returns [Error](struct.Error.html) with CNTK message instead of panicking.
+//!
+//! ## Data layout
+//!
+//! CNTK shapes list axes from the fastest changing one (column-major order), followed by sequence
+//! and batch axes. Ndarrays passed to `*_from_ndarray` functions use the reversed axis order, so
+//! CNTK shape [3, 2] of a batch of sequences corresponds to ndarray shape [batch, sequence, 2, 3].
+//! Ndarrays can have any memory layout: standard (row-major) ones are used directly, transposed
+//! views, slices and Fortran-order arrays are packed into CNTK order first. An ndarray in CNTK axis
+//! order can be passed as `array.t()`. Output ndarrays use the same reversed order by default, or
+//! CNTK axis order with column-major layout via [NDArrayLayout](enum.NDArrayLayout.html).
 
 #[macro_use]
 extern crate cpp;
 
-#[macro_use(array)]
+#[macro_use(array, s)]
 extern crate ndarray;
 
+mod error;
+pub use error::Error;
+
+mod data_type;
+pub use data_type::{DataType, CntkElement};
+
 mod shape;
 pub use shape::Shape;
 
@@ -58,19 +80,26 @@ mod variable_set;
 pub use variable_set::VariableSet;
 
 mod variable;
-pub use variable::{Variable, ParameterInitializer};
+pub use variable::{Variable, VariableKind, ParameterInitializer, Parameter, Constant, InputVariable, Placeholder, InputVariableBuilder};
 
 pub mod ops;
+pub mod adversarial;
 
 mod function;
-pub use function::Function;
+pub use function::{Function, GraphNode, ParameterCloningMethod};
 pub use function::BackPropState;
 
+mod attribute;
+pub use attribute::AttributeValue;
+
 mod axis;
 pub use axis::Axis;
 
+mod nd_array_view;
+pub use nd_array_view::{NDArrayView, NDArrayLayout};
+
 mod value;
-pub use value::Value;
+pub use value::{Value, BorrowedValue, SparseCsc, MaskKind};
 
 mod device;
 pub use device::{DeviceDescriptor, set_max_num_cpu_threads};
@@ -83,6 +112,10 @@ pub use data_map::DataMap;
 mod replacement_map;
 pub use replacement_map::ReplacementMap;
 
+mod user_function;
+pub use user_function::{UserFunction, UserFunctionDeserializer, user_function, try_user_function, register_user_function_deserializer,
+                        try_register_user_function_deserializer};
+
 mod learner;
 pub use learner::{Learner, DoubleParameterSchedule};
 
@@ -136,6 +169,115 @@ mod tests {
         }
     }
 
+    #[test]
+    fn borrowed_value() {
+        let var = Variable::input_variable(&Shape::new(vec!(5)));
+        let var2 = Variable::input_variable(&Shape::new(vec!(5)));
+        let plus = plus(&var, &var2);
+
+        let data: Vec<f32> = vec!(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0);
+        let data2 = array![[11f32, 12., 13., 14., 15.], [16., 17., 18., 19., 110.]];
+        let val = Value::borrow_batch(&var.shape(), &data);
+        let val2 = Value::borrow_batch_ndarray(&var2.shape(), &data2);
+
+        let mut datamap = DataMap::new();
+        datamap.add_borrowed(&var, &val);
+        datamap.add_borrowed(&var2, &val2);
+        let mut outdatamap = outdatamap! {&plus};
+
+        plus.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+        assert_eq!(val.to_vec(), data);
+
+        let mut result = vec![0f32; 10];
+        outdatamap.get(&plus).unwrap().copy_to_slice(&mut result);
+        assert_eq!(result, vec!(13., 16., 19., 22., 25., 28., 31., 34., 37., 130.));
+        assert!(Value::try_borrow_batch(&var.shape(), &data[..7]).is_err());
+
+        let mut result_array = ::ndarray::Array3::<f32>::zeros((2, 1, 5));
+        outdatamap.get(&plus).unwrap().copy_to_ndarray(&mut result_array);
+        assert_eq!(result_array, array![[[13., 16., 19., 22., 25.]], [[28., 31., 34., 37., 130.]]]);
+
+        let mut output = vec![0f32; 10];
+        let copied;
+        let outdatamap = {
+            let out_val = Value::borrow_batch_mut(&plus.to_variable().unwrap().shape(), &mut output);
+            let mut outdatamap = DataMap::new();
+            outdatamap.add_borrowed(&plus, &out_val);
+            plus.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+            copied = outdatamap.get(&plus).unwrap();
+            outdatamap
+        };
+        assert_eq!(output, vec!(13., 16., 19., 22., 25., 28., 31., 34., 37., 130.));
+        // values read from DataMap own their data, erased borrowed values are gone
+        assert_eq!(copied.to_vec(), output);
+        assert!(outdatamap.get(&plus).is_none());
+    }
+
+    #[test]
+    fn ndarray_layouts() {
+        use ndarray::ShapeBuilder;
+        let var = Variable::input_variable(&Shape::new(vec!(2, 3)));
+        let out = plus(&var, &Variable::constant_scalar(1.0));
+
+        let data = array![[1f32, 2., 3.], [4., 5., 6.]];
+        let val = Value::from_ndarray(&var.shape(), &data.t(), DeviceDescriptor::cpu());
+        assert_eq!(val.to_vec(), vec!(1., 4., 2., 5., 3., 6.));
+
+        let data_f = ::ndarray::Array::from_shape_vec((3, 2).f(), vec!(1f32, 2., 3., 4., 5., 6.)).unwrap();
+        let val = Value::batch_from_ndarray(&var.shape(), &data_f.slice(s![.., ..]), DeviceDescriptor::cpu());
+
+        let datamap = datamap! {&var => &val};
+        let mut outdatamap = outdatamap! {&out};
+        out.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+
+        let result = outdatamap.get(&out).unwrap();
+        let result_array = result.to_ndarray();
+        assert_eq!(result_array.shape(), &[1, 1, 3, 2]);
+        assert_eq!(result_array.iter().cloned().collect::<Vec<_>>(), vec!(2., 5., 3., 6., 4., 7.));
+        assert_eq!(result.to_ndarray_with_layout::<f32>(NDArrayLayout::ColumnMajor), result.to_ndarray().t());
+    }
+
+    #[test]
+    fn double_precision() {
+        let var = Variable::input_variable_of::<f64>(&Shape::new(vec!(2)));
+        let par = Variable::parameter_of::<f64>(&Shape::new(vec!(2)), &ParameterInitializer::constant(0.5), DeviceDescriptor::cpu());
+        let out = plus(&var, &par);
+        let out_float = cast(&out, DataType::Float);
+        assert_eq!(var.data_type(), DataType::Double);
+
+        let data: Vec<f64> = vec!(1.0, 2.0, 3.0, 4.0);
+        let val = Value::batch_from_vec_of(&var.shape(), &data, DeviceDescriptor::cpu());
+        assert_eq!(val.data_type(), DataType::Double);
+
+        let datamap = datamap! {&var => &val};
+        let mut outdatamap = outdatamap! {&out, &out_float};
+        Function::combine(&[&Variable::from(&out), &Variable::from(&out_float)]).evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+
+        assert_eq!(outdatamap.get(&out).unwrap().to_vec_of::<f64>(), vec!(1.5, 2.5, 3.5, 4.5));
+        assert_eq!(outdatamap.get(&out_float).unwrap().to_vec(), vec!(1.5, 2.5, 3.5, 4.5));
+        assert_eq!(par.parameter_to_vec_of::<f64>(), vec!(0.5, 0.5));
+    }
+
+    #[test]
+    fn test_nd_array_view() {
+        let view = NDArrayView::from_slice(&Shape::new(vec!(2, 3)), &[1f32, 2., 3., 4., 5., 6.], DeviceDescriptor::cpu());
+        assert_eq!(view.to_ndarray(), array![[1., 2.], [3., 4.], [5., 6.]].into_dyn());
+
+        let slice = view.slice_view(&[0, 1], &[2, 2], true);
+        assert!(slice.is_read_only());
+        assert_eq!(slice.to_vec(), vec!(3., 4., 5., 6.));
+
+        let mut copy = view.deep_clone(false);
+        copy.copy_from(&NDArrayView::from_slice(&Shape::new(vec!(2, 3)), &[0f32; 6], DeviceDescriptor::cpu()));
+        assert_eq!(copy.to_vec(), vec!(0.; 6));
+        assert_eq!(view.alias(true).to_vec(), vec!(1., 2., 3., 4., 5., 6.));
+
+        let par = Variable::parameter(&Shape::new(vec!(2)), &ParameterInitializer::constant(3.), DeviceDescriptor::cpu());
+        assert_eq!(par.value().to_vec(), vec!(3., 3.));
+        let val = Value::from_nd_array_view(&view);
+        assert_eq!(val.data().to_vec(), view.to_vec());
+    }
+
     #[test]
     fn gradient() {
         let var = Variable::input_variable_with_gradient(&Shape::scalar());
@@ -438,6 +580,425 @@ mod tests {
         assert_eq!(result, expected_output);
     }
 
+    #[test]
+    fn test_shape() {
+        let shape = Shape::from(&[2, 3, 4][..]);
+        assert_eq!(shape, Shape::new(vec!(2, 3, 4)));
+        assert_eq!(shape.clone(), shape);
+        assert_eq!(shape.sub_shape(1, 3), Shape::new(vec!(3, 4)));
+        assert!(shape.try_sub_shape(2, 4).is_err());
+        assert_eq!(format!("{}", shape), "[2 x 3 x 4]");
+        assert_eq!(format!("{:?}", Shape::new(vec!(Shape::FREE_DIMENSION, 3))), "Shape[* x 3]");
+        assert!(Shape::unknown().is_unknown());
+        assert_eq!(shape.try_total_size().unwrap(), 24);
+        assert!(Shape::new(vec!(Shape::FREE_DIMENSION, 3)).try_total_size().is_err());
+        assert!(Shape::new(vec!(Shape::INFERRED_DIMENSION)).try_total_size().is_err());
+        assert!(!shape.is_unknown());
+
+        let var = Variable::input_variable(&Shape::new(vec!(3)));
+        let par = Variable::parameter(&Shape::new(vec!(2, Shape::INFERRED_DIMENSION)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        assert!(par.shape().has_inferred_dimension());
+        let _out = times(&par, &var);
+        assert_eq!(par.shape(), Shape::new(vec!(2, 3)));
+    }
+
+    #[test]
+    fn test_axis() {
+        let axis = Axis::new(1);
+        assert!(axis.is_static());
+        assert!(!axis.is_dynamic());
+        assert_eq!(axis.static_index(), Some(1));
+        assert_eq!(axis, Axis::new(1));
+        assert!(axis != Axis::new(0));
+
+        let dynamic = Axis::named_dynamic("label");
+        assert!(dynamic.is_dynamic());
+        assert_eq!(dynamic.name(), "label");
+        assert_eq!(dynamic.static_index(), None);
+        assert_eq!(Axis::default_batch_axis(), Axis::default_batch_axis());
+        assert!(Axis::end_static_axis().is_static());
+        assert!(Axis::operand_sequence_axis().is_dynamic());
+    }
+
+    #[test]
+    fn test_variable_introspection() {
+        let var = Variable::create(&Shape::new(vec!(3)), true, false, "x", &[Axis::named_dynamic("seq"), Axis::default_batch_axis()]);
+        let par = Variable::parameter(&Shape::new(vec!(2, 3)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let out = Variable::from(times(&par, &var));
+
+        assert_eq!(var.kind(), VariableKind::Input);
+        assert_eq!(par.kind(), VariableKind::Parameter);
+        assert_eq!(out.kind(), VariableKind::Output);
+        assert_eq!(Variable::placeholder(&Shape::unknown()).kind(), VariableKind::Placeholder);
+        assert!(Variable::constant_scalar(1.).is_constant());
+        assert!(var.is_sparse());
+        assert!(!var.needs_gradient());
+        assert!(par.needs_gradient());
+        assert_eq!(var.dynamic_axes(), vec!(Axis::named_dynamic("seq"), Axis::default_batch_axis()));
+        assert!(var.owner().is_none());
+        assert_eq!(out.owner().unwrap().inputs().len(), 2);
+        assert!(var.uid() != par.uid());
+
+        let mut map = ::std::collections::HashMap::new();
+        map.insert(var.to_variable(), 1);
+        map.insert(par.to_variable(), 2);
+        assert_eq!(map[&*var], 1);
+        assert_eq!(map[&*par], 2);
+        assert!(var == var.clone());
+        assert!(*var != *par);
+    }
+
+    #[test]
+    fn test_parameter_value() {
+        let var = Variable::input_variable(&Shape::new(vec!(3)));
+        let par = Variable::parameter(&Shape::new(vec!(2, 3)), &ParameterInitializer::constant(0.), DeviceDescriptor::cpu());
+        let bias = Variable::constant_from_ndarray(&Shape::new(vec!(2)), &array![10f32, 20.], DeviceDescriptor::cpu());
+        let out = plus(times(&par, &var), &bias);
+
+        par.set_parameter_value(&array![[1f32, 2.], [3., 4.], [5., 6.]]);
+        assert_eq!(par.parameter_value(), array![[1., 2.], [3., 4.], [5., 6.]].into_dyn());
+        assert_eq!(bias.constant_value(), array![10., 20.].into_dyn());
+        assert!(par.try_set_parameter_value(&array![1f32, 2.]).is_err());
+        assert!(par.try_parameter_value_of::<f64>().is_err());
+
+        let par64 = Variable::parameter_of::<f64>(&Shape::new(vec!(2)), &ParameterInitializer::constant(0.5), DeviceDescriptor::cpu());
+        assert_eq!(par64.parameter_value_of::<f64>(), array![0.5, 0.5].into_dyn());
+        let const64 = Variable::constant_from_slice(&Shape::new(vec!(2)), &[1f64, 2.], DeviceDescriptor::cpu());
+        assert_eq!(const64.constant_value_of::<f64>(), array![1., 2.].into_dyn());
+        assert!(const64.try_constant_value().is_err());
+
+        let val = Value::batch_from_vec(&var.shape(), &[1f32, 1., 1.], DeviceDescriptor::cpu());
+        let datamap = datamap! {&var => &val};
+        let mut outdatamap = outdatamap! {&out};
+        out.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+
+        assert_eq!(outdatamap.get(&out).unwrap().to_vec(), vec!(19., 32.));
+    }
+
+    #[test]
+    fn test_typed_variables() {
+        let var = Variable::input_variable(&Shape::new(vec!(3)));
+        let par = Variable::parameter(&Shape::new(vec!(2, 3)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let out = times(&par, &var);
+
+        let params = out.parameters();
+        assert_eq!(params, vec!(par.clone()));
+        assert!(Parameter::from_variable(par.to_variable()).is_some());
+        assert!(Parameter::from_variable(var.to_variable()).is_none());
+        assert!(InputVariable::from_variable(var.to_variable()).is_some());
+        assert!(Constant::from_variable(Variable::from(&out)).is_none());
+        let _learner = Learner::sgd(&params, &DoubleParameterSchedule::constant(0.01));
+    }
+
+    #[test]
+    fn test_input_builder() {
+        let seq_axis = Axis::named_dynamic("words");
+        let var = Variable::input(&Shape::new(vec!(3))).name("X").sparse().needs_gradient().sequence_axis(&seq_axis).dtype(DataType::Double).build();
+        assert_eq!(var.name(), "X");
+        assert!(var.is_sparse());
+        assert!(var.needs_gradient());
+        assert_eq!(var.dynamic_axes(), vec!(seq_axis, Axis::default_batch_axis()));
+        assert_eq!(var.data_type(), DataType::Double);
+
+        let default = Variable::input(&Shape::new(vec!(3))).build();
+        assert_eq!(default.name(), "");
+        assert!(!default.is_sparse() && !default.needs_gradient());
+        assert_eq!(default.dynamic_axes().len(), 2);
+        assert_eq!(default.data_type(), DataType::Float);
+
+        let static_only = Variable::input(&Shape::new(vec!(3))).dynamic_axes::<Axis>(&[]).build();
+        assert!(static_only.dynamic_axes().is_empty());
+    }
+
+    #[test]
+    fn test_function_introspection() {
+        let var = Variable::input_variable(&Shape::new(vec!(3)));
+        let sum = reduce_sum(&var, &Axis::new(0));
+        assert!(sum.is_composite());
+        assert!(!sum.is_primitive());
+        assert!(!sum.is_block());
+        assert!(sum.block_root().is_none());
+
+        let root = sum.root_function();
+        assert!(root.is_primitive());
+        assert_eq!(root.op_name(), "ReduceElements");
+        match root.attributes().get("axis") {
+            Some(&AttributeValue::Axis(ref axis)) => assert_eq!(*axis, Axis::new(0)),
+            other => panic!("Unexpected axis attribute {:?}", other),
+        }
+        match root.attributes().get("reductionOpName") {
+            Some(&AttributeValue::String(ref op)) => assert_eq!(op, "Sum"),
+            other => panic!("Unexpected reductionOpName attribute {:?}", other),
+        }
+
+        let named = named_alias(&sum, "total");
+        assert_eq!(named.name(), "total");
+        assert!(named.try_set_name("other").is_err());
+        let unnamed = plus(&var, &var);
+        unnamed.set_name("double");
+        assert_eq!(unnamed.name(), "double");
+        assert!(unnamed.uid() != named.uid());
+    }
+
+    #[test]
+    fn test_graph_traversal() {
+        let x = Variable::input_variable_with_name(&Shape::new(vec!(3)), "X");
+        let w = Variable::parameter(&Shape::new(vec!(2, 3)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let b = Variable::parameter(&Shape::new(vec!(2)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let hidden = named_alias(times(&w, &x), "hidden");
+        let output = named_alias(plus(tanh(&hidden), &b), "output");
+
+        let found = output.find_by_name("hidden").unwrap();
+        assert_eq!(Variable::from(&found), Variable::from(&hidden));
+        assert!(output.find_by_name("missing").is_none());
+        assert_eq!(output.find_all_by_name("output").len(), 1);
+        assert!(output.try_find_all_by_name("missing").unwrap().is_empty());
+
+        let mut op_names = Vec::new();
+        let mut variables = Vec::new();
+        output.visit(|node| match *node {
+            GraphNode::Function(ref f) => op_names.push(f.op_name()),
+            GraphNode::Variable(ref v) => variables.push(v.clone()),
+        });
+        assert_eq!(op_names, vec!("Alias", "Plus", "Tanh", "Alias", "Times"));
+        assert_eq!(variables.len(), 7);
+        assert!(variables.contains(&*x) && variables.contains(&*w) && variables.contains(&*b));
+    }
+
+    #[test]
+    fn test_deep_clone() {
+        let x = Variable::input_variable(&Shape::new(vec!(2)));
+        let w = Variable::parameter(&Shape::new(vec!(2, 2)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let model = times(&w, &x);
+
+        let shared = model.deep_clone(ParameterCloningMethod::Share);
+        assert_eq!(shared.parameters(), vec!(w.clone()));
+
+        let cloned = model.deep_clone(ParameterCloningMethod::Clone);
+        assert_eq!(cloned.parameters().len(), 1);
+        assert!(cloned.parameters()[0] != w);
+        assert_eq!(cloned.parameters()[0].parameter_to_vec(), vec!(1., 1., 1., 1.));
+
+        let frozen = model.deep_clone(ParameterCloningMethod::Freeze);
+        assert_eq!(frozen.num_parameters(), 0);
+
+        let x2 = Variable::input_variable(&Shape::new(vec!(2)));
+        let grafted = model.deep_clone_with_replacements(ParameterCloningMethod::Share, &[(&x, &x2)]);
+        assert!(grafted.inputs().contains(&*x2));
+        assert!(!grafted.inputs().contains(&*x));
+
+        let val = Value::batch_from_vec(&x2.shape(), &[1f32, 2.], DeviceDescriptor::cpu());
+        let datamap = datamap!{&x2 => &val};
+        let mut outdatamap = outdatamap!{&grafted};
+        grafted.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+        assert_eq!(outdatamap.get(&grafted).unwrap().to_vec(), vec!(3., 3.));
+    }
+
+    #[test]
+    fn test_as_block() {
+        let x = Variable::input_variable(&Shape::new(vec!(2)));
+        let arg = Variable::placeholder(&Shape::new(vec!(2)));
+        let w = Variable::parameter(&Shape::new(vec!(2, 2)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let layer = tanh(times(&w, &arg));
+
+        let block = Function::as_block(&layer, &[(&arg, &x)], "Dense", "dense1");
+        let root = block.root_function();
+        assert!(root.is_block());
+        assert_eq!(root.op_name(), "Dense");
+        assert_eq!(root.name(), "dense1");
+        assert_eq!(root.block_root().unwrap().root_function().op_name(), "Tanh");
+        assert_eq!(root.block_arguments_mapping(), vec!((arg.to_variable(), x.to_variable())));
+        assert_eq!(block.parameters(), vec!(w.clone()));
+        assert_eq!(block.find_by_name("dense1").map(|f| f.uid()), Some(root.uid()));
+
+        let mut op_names = Vec::new();
+        block.visit(|node| if let GraphNode::Function(ref f) = *node { op_names.push(f.op_name()) });
+        assert_eq!(op_names, vec!("Dense"));
+
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, -1.], DeviceDescriptor::cpu());
+        let datamap = datamap!{&x => &val};
+        let mut outdatamap = outdatamap!{&block};
+        block.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+        assert_eq!(outdatamap.get(&block).unwrap().to_vec(), vec!(0., 0.));
+    }
+
+    struct Scale {
+        factor: f32,
+    }
+
+    impl UserFunction for Scale {
+        fn op_name(&self) -> String {
+            "RustScale".to_string()
+        }
+
+        fn output_shapes(&self, inputs: &[Variable]) -> Vec<Shape> {
+            vec!(inputs[0].shape())
+        }
+
+        fn forward(&self, inputs: &[Value], _device: DeviceDescriptor) -> Vec<Value> {
+            let data = inputs[0].to_vec().iter().map(|x| x * self.factor).collect::<Vec<_>>();
+            vec!(Value::from_vec_like(&inputs[0], &data))
+        }
+
+        fn backward(&self, _inputs: &[Value], _outputs: &[Value], output_gradients: &[Option<Value>]) -> Vec<Option<Value>> {
+            let gradient = output_gradients[0].as_ref().unwrap();
+            let data = gradient.to_vec().iter().map(|x| x * self.factor).collect::<Vec<_>>();
+            vec!(Some(Value::from_vec_like(gradient, &data)))
+        }
+
+        fn state(&self) -> String {
+            self.factor.to_string()
+        }
+    }
+
+    fn deserialize_scale(state: &str) -> Box<dyn UserFunction> {
+        Box::new(Scale { factor: state.parse().unwrap() })
+    }
+
+    #[test]
+    fn test_user_function() {
+        let x = Variable::input_variable_with_gradient(&Shape::new(vec!(2)));
+        let scaled = user_function(Scale { factor: 3. }, &[&x], "scale");
+        let out = plus(&scaled, &x);
+        assert_eq!(scaled.root_function().op_name(), "RustScale");
+        assert_eq!(scaled.outputs()[0].shape(), x.shape());
+
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, 2.], DeviceDescriptor::cpu());
+        let datamap = datamap!{&x => &val};
+        let mut outdatamap = outdatamap!{&out};
+        let bpstate = out.forward(&datamap, &mut outdatamap, DeviceDescriptor::cpu(), &variableset!{&out}, &VariableSet::new());
+        assert_eq!(outdatamap.get(&out).unwrap().to_vec(), vec!(4., 8.));
+
+        let out_val = outdatamap.get(&out).unwrap();
+        let rootgrad = Value::from_vec(&out_val.shape(), &vec![1f32; out_val.shape().total_size()], DeviceDescriptor::cpu());
+        let mut result = DataMap::new();
+        result.add_null(&x);
+        out.backward(&bpstate, &datamap!{&out => &rootgrad}, &mut result);
+        assert_eq!(result.get(&x).unwrap().to_vec(), vec!(4., 4.));
+
+        register_user_function_deserializer("RustScale", deserialize_scale);
+        assert!(try_register_user_function_deserializer("RustScale", deserialize_scale).is_err());
+        let path = ::std::env::temp_dir().join(format!("cntk_rs_{}_{}.model", ::std::process::id(), out.uid()));
+        out.save(path.to_str().unwrap());
+        let loaded = Function::load(path.to_str().unwrap(), DeviceDescriptor::cpu());
+        let loaded_x = loaded.inputs().into_iter().find(|v| v.is_input()).unwrap();
+        let mut outdatamap = outdatamap!{&loaded};
+        loaded.evaluate(&datamap!{&loaded_x => &val}, &mut outdatamap, DeviceDescriptor::cpu());
+        assert_eq!(outdatamap.get(&loaded).unwrap().to_vec(), vec!(4., 8.));
+        ::std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_user_function_panic() {
+        struct Failing;
+
+        impl UserFunction for Failing {
+            fn op_name(&self) -> String {
+                "RustFailing".to_string()
+            }
+
+            fn output_shapes(&self, inputs: &[Variable]) -> Vec<Shape> {
+                vec!(inputs[0].shape())
+            }
+
+            fn forward(&self, _inputs: &[Value], _device: DeviceDescriptor) -> Vec<Value> {
+                panic!("forward failed")
+            }
+
+            fn backward(&self, _inputs: &[Value], _outputs: &[Value], _output_gradients: &[Option<Value>]) -> Vec<Option<Value>> {
+                unreachable!()
+            }
+        }
+
+        let x = Variable::input_variable(&Shape::new(vec!(2)));
+        let out = user_function(Failing, &[&x], "");
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, 2.], DeviceDescriptor::cpu());
+        let mut outdatamap = outdatamap!{&out};
+        let error = out.try_evaluate(&datamap!{&x => &val}, &mut outdatamap, DeviceDescriptor::cpu()).unwrap_err();
+        assert!(format!("{}", error).contains("forward failed"));
+    }
+
+    #[test]
+    fn test_gradients() {
+        let x = Variable::input_variable_with_gradient(&Shape::new(vec!(2)));
+        let w = Variable::parameter(&Shape::new(vec!(2)), &ParameterInitializer::constant(3.), DeviceDescriptor::cpu());
+        let product = element_times(&w, &x);
+        let loss = reduce_sum(&product, &Axis::all_static());
+
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, 2.], DeviceDescriptor::cpu());
+        let datamap = datamap!{&x => &val};
+        let grads = loss.gradients(&datamap, &[&*x, &*w], DeviceDescriptor::cpu());
+        assert_eq!(grads[&*x].to_vec(), vec!(3., 3.));
+        assert_eq!(grads[&*w].to_vec(), vec!(1., 2.));
+
+        let mut outdatamap = DataMap::new();
+        let grads = loss.forward_and_gradients(&datamap, &mut outdatamap, &[&x], None, DeviceDescriptor::cpu());
+        assert_eq!(outdatamap.get(&loss).unwrap().to_vec(), vec!(9.));
+        assert_eq!(grads[&*x].to_vec(), vec!(3., 3.));
+
+        assert!(product.try_gradients(&datamap, &[&x], DeviceDescriptor::cpu()).is_err());
+        let c = Variable::constant_repeat(&Shape::new(vec!(2)), 1.);
+        let loss_c = reduce_sum(&element_times(&c, &x), &Axis::all_static());
+        assert!(loss_c.try_gradients(&datamap, &[&*x, &*c], DeviceDescriptor::cpu()).is_err());
+        let mut outdatamap = outdatamap!{&product};
+        product.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+        let root = Value::from_vec_like(&outdatamap.get(&product).unwrap(), &[1f32, 10.]);
+        let grads = product.forward_and_gradients(&datamap, &mut DataMap::new(), &[&x], Some(&root), DeviceDescriptor::cpu());
+        assert_eq!(grads[&*x].to_vec(), vec!(3., 30.));
+    }
+
+    #[test]
+    fn test_adversarial() {
+        let x = Variable::input_variable_with_gradient(&Shape::new(vec!(2)));
+        let y = Variable::input_variable(&Shape::new(vec!(2)));
+        let w = Variable::parameter(&Shape::new(vec!(2, 2)), &ParameterInitializer::constant(0.), DeviceDescriptor::cpu());
+        w.set_parameter_value(&array![[1f32, -1.], [2., 0.]]);
+        let logits = times(&w, &x);
+        let loss = cross_entropy_with_softmax(&logits, &y);
+
+        let xval = Value::batch_from_vec(&x.shape(), &[0.5f32, 0.5], DeviceDescriptor::cpu());
+        let yval = Value::batch_from_vec(&y.shape(), &[1f32, 0.], DeviceDescriptor::cpu());
+        let datamap = datamap!{&x => &xval, &y => &yval};
+
+        let saliency = adversarial::input_gradient(&logits, &datamap, &x, Some(1), DeviceDescriptor::cpu());
+        assert_eq!(saliency.to_vec(), vec!(-1., 0.));
+        let saliency = adversarial::input_gradient(&logits, &datamap, &x, None, DeviceDescriptor::cpu());
+        assert_eq!(saliency.to_vec(), vec!(0., 2.));
+
+        let perturbed = adversarial::fgsm(&loss, &datamap, &x, &adversarial::AttackOptions::new(0.1).value_range(0., 1.), DeviceDescriptor::cpu());
+        let grad = loss.gradients(&datamap, &[&x], DeviceDescriptor::cpu())[&*x].to_vec();
+        let expected = grad.iter().map(|g| 0.5 + 0.1 * g.signum()).collect::<Vec<f32>>();
+        assert_eq!(perturbed.to_vec(), expected);
+
+        let options = adversarial::AttackOptions::new(0.2).step_size(0.1).num_steps(5).value_range(0., 1.);
+        let attacked = adversarial::pgd(&loss, &datamap, &x, &options, DeviceDescriptor::cpu());
+        assert!(attacked.to_vec().iter().all(|v| *v >= 0.3 - 1e-6 && *v <= 0.7 + 1e-6));
+        let attacked_loss = |value: &Value| {
+            let mut outdatamap = outdatamap!{&loss};
+            loss.evaluate(&datamap!{&x => value, &y => &yval}, &mut outdatamap, DeviceDescriptor::cpu());
+            outdatamap.get(&loss).unwrap().to_vec()[0]
+        };
+        assert!(attacked_loss(&attacked) > attacked_loss(&xval));
+
+        let x64 = Variable::input(&Shape::new(vec!(2))).dtype(DataType::Double).needs_gradient().build();
+        let x64val = Value::batch_from_vec_of::<f64>(&x64.shape(), &[0.5, 0.5], DeviceDescriptor::cpu());
+        let error = adversarial::try_fgsm(&plus(&x64, &x64), &datamap!{&x64 => &x64val}, &x64, &adversarial::AttackOptions::new(0.1), DeviceDescriptor::cpu());
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn data_map_add_and_set() {
+        let x = Variable::input_variable(&Shape::new(vec!(2)));
+        let first = Value::batch_from_vec(&x.shape(), &[1f32, 2.], DeviceDescriptor::cpu());
+        let second = Value::batch_from_vec(&x.shape(), &[3f32, 4.], DeviceDescriptor::cpu());
+        let mut datamap = DataMap::new();
+        datamap.add(&x, &first);
+        datamap.add(&x, &second);
+        assert_eq!(datamap.get(&x).unwrap().to_vec(), vec!(1., 2.));
+        datamap.set(&x, &second);
+        assert_eq!(datamap.get(&x).unwrap().to_vec(), vec!(3., 4.));
+    }
+
     #[test]
     fn test_transpose() {
         test_single_arg_func(|x| {
@@ -595,6 +1156,106 @@ mod tests {
         assert_eq!(result.len(), 12);
     }
 
+    #[test]
+    fn test_convolution_options() {
+        let kernel = Variable::parameter(&Shape::new(vec!(3)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        test_single_arg_func(|x| {
+            convolution_with_options(&kernel, x, &ConvolutionOptions::new().auto_padding(&[false]).dilation(&Shape::new(vec!(2))).reduction_rank(0))
+        }, &Shape::new(vec!(5)), &[1., 2., 3., 4., 5.], &[9.]);
+
+        test_single_arg_func(|x| {
+            let options = ConvolutionOptions::new()
+                .padding(&Shape::new(vec!(2)), &Shape::new(vec!(2)))
+                .dilation(&Shape::new(vec!(2)))
+                .strides(&Shape::new(vec!(2)))
+                .reduction_rank(0);
+            convolution_with_options(&kernel, x, &options)
+        }, &Shape::new(vec!(5)), &[1., 2., 3., 4., 5.], &[4., 9., 8.]);
+
+        let kernel = Variable::parameter(&Shape::new(vec!(2)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        test_single_arg_func(|x| {
+            convolution_transpose(&kernel, x, &ConvolutionOptions::new().strides(&Shape::new(vec!(2))).auto_padding(&[false]).reduction_rank(0))
+        }, &Shape::new(vec!(3)), &[1., 2., 3.], &[1., 1., 2., 2., 3., 3.]);
+
+        let var = Variable::input_variable(&Shape::new(vec!(3)));
+        assert!(try_convolution_transpose(&kernel, &var, &ConvolutionOptions::new().groups(2)).is_err());
+    }
+
+    #[test]
+    fn test_pooling_options() {
+        test_single_arg_func(|x| {
+            pooling(x, PoolingType::Average, &Shape::new(vec!(2)), &PoolingOptions::new().strides(&Shape::new(vec!(2))).ceil_out_dim(true))
+        }, &Shape::new(vec!(5)), &[1., 2., 3., 4., 5.], &[1.5, 3.5, 5.]);
+
+        test_single_arg_func(|x| {
+            pooling(x, PoolingType::Average, &Shape::new(vec!(3)), &PoolingOptions::new().auto_padding(&[true]))
+        }, &Shape::new(vec!(5)), &[1., 2., 3., 4., 5.], &[1.5, 2., 3., 4., 4.5]);
+
+        test_single_arg_func(|x| {
+            pooling(x, PoolingType::Average, &Shape::new(vec!(3)), &PoolingOptions::new().auto_padding(&[true]).include_pad(true))
+        }, &Shape::new(vec!(5)), &[1., 2., 3., 4., 5.], &[1., 2., 3., 4., 3.]);
+
+        test_single_arg_func(|x| {
+            let options = PoolingOptions::new().strides(&Shape::new(vec!(2)));
+            unpooling(pooling(x, PoolingType::Max, &Shape::new(vec!(2)), &options), x, &Shape::new(vec!(2)), &options)
+        }, &Shape::new(vec!(4)), &[1., 3., 2., 0.], &[0., 3., 2., 0.]);
+    }
+
+    #[test]
+    fn test_batch_normalization() {
+        let x = Variable::input_variable(&Shape::new(vec!(2)));
+        let scale = Variable::parameter(&Shape::new(vec!(2)), &ParameterInitializer::constant(1.), DeviceDescriptor::cpu());
+        let bias = Variable::parameter(&Shape::new(vec!(2)), &ParameterInitializer::constant(0.), DeviceDescriptor::cpu());
+        let running_mean = Variable::constant_repeat(&Shape::new(vec!(2)), 0.);
+        let running_variance = Variable::constant_repeat(&Shape::new(vec!(2)), 1.);
+        let running_count = Variable::constant_scalar(0.);
+        let options = BatchNormalizationOptions::new().normalization_time_constant(f64::INFINITY).use_cudnn_engine(false);
+        let bn = batch_normalization(&x, &scale, &bias, &running_mean, &running_variance, &running_count, &options);
+
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, 2., 3., 4.], DeviceDescriptor::cpu());
+        let datamap = datamap!{&x => &val};
+        let evaluate = || {
+            let mut outdatamap = outdatamap!{&bn};
+            bn.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+            outdatamap.get(&bn).unwrap().to_vec()
+        };
+
+        let result = evaluate();
+        for (r, e) in result.iter().zip(&[1., 2., 3., 4.]) {
+            assert!((r - e).abs() < 1e-3);
+        }
+
+        let loss = reduce_sum(&bn, &Axis::all());
+        loss.gradients(&datamap, &[&scale], DeviceDescriptor::cpu());
+        assert_eq!(running_mean.constant_value().into_raw_vec(), vec!(2., 3.));
+
+        let result = evaluate();
+        assert!(result[0] < 0. && result[1] < 0. && result[2] > 0. && result[3] > 0.);
+    }
+
+    #[test]
+    fn test_layer_and_local_response_normalization() {
+        let x = Variable::input_variable(&Shape::new(vec!(4)));
+        let ln = layer_normalization(&x, Variable::constant_scalar(2.), Variable::constant_scalar(1.), 0.);
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, 2., 3., 4.], DeviceDescriptor::cpu());
+        let mut outdatamap = outdatamap!{&ln};
+        ln.evaluate(&datamap!{&x => &val}, &mut outdatamap, DeviceDescriptor::cpu());
+        let result = outdatamap.get(&ln).unwrap().to_vec();
+        for (r, e) in result.iter().zip(&[-1.6833, 0.1056, 1.8944, 3.6833]) {
+            assert!((r - e).abs() < 1e-3);
+        }
+
+        let x = Variable::input_variable(&Shape::new(vec!(1, 1, 2)));
+        let lrn = local_response_normalization(&x, 0, 1., 1., 1.);
+        let val = Value::batch_from_vec(&x.shape(), &[1f32, 2.], DeviceDescriptor::cpu());
+        let mut outdatamap = outdatamap!{&lrn};
+        lrn.evaluate(&datamap!{&x => &val}, &mut outdatamap, DeviceDescriptor::cpu());
+        let result = outdatamap.get(&lrn).unwrap().to_vec();
+        for (r, e) in result.iter().zip(&[0.5, 0.4]) {
+            assert!((r - e).abs() < 1e-5);
+        }
+    }
+
     #[test]
     fn test_sparse() {
         let var = Variable::input_variable(&Shape::new(vec!(5, 5)));
@@ -620,6 +1281,41 @@ mod tests {
         assert_eq!(result, vec!(0., 1., 0., 0., 0., 0., 0., 0., 1., 0.));
     }
 
+    #[test]
+    fn test_sparse_csc() {
+        let var = Variable::sparse_input_variable(&Shape::new(vec!(5)));
+        let var2 = Variable::parameter(&Shape::new(&vec!(2, 5)), &ParameterInitializer::constant(2.), DeviceDescriptor::cpu());
+
+        let output = times(&var2, &var);
+
+        let data = SparseCsc { col_starts: vec!(0, 2, 3), row_indices: vec!(1, 3, 4), values: vec!(1.0f32, 0.5, 3.0) };
+        let val = Value::sequence_from_csc(&var.shape(), 2, &data, DeviceDescriptor::cpu());
+        assert!(val.is_sparse());
+        assert_eq!(val.to_csc(), data);
+
+        let datamap = datamap!{&var => &val};
+        let mut outdatamap = outdatamap!{&output};
+
+        output.evaluate(&datamap, &mut outdatamap, DeviceDescriptor::cpu());
+
+        let result = outdatamap.get(&output).unwrap().to_vec();
+
+        assert_eq!(result, vec!(3., 3., 6., 6.));
+        assert!(Value::try_sequence_from_csc(&var.shape(), 3, &data, DeviceDescriptor::cpu()).is_err());
+
+        let invalid = [
+            SparseCsc { col_starts: vec!(0, 2, 3), row_indices: vec!(1, 5, 4), values: vec!(1.0f32, 0.5, 3.0) },
+            SparseCsc { col_starts: vec!(0, 2, 3), row_indices: vec!(1, -1, 4), values: vec!(1.0f32, 0.5, 3.0) },
+            SparseCsc { col_starts: vec!(0, 4, 3), row_indices: vec!(1, 3, 4), values: vec!(1.0f32, 0.5, 3.0) },
+            SparseCsc { col_starts: vec!(1, 2, 3), row_indices: vec!(1, 3, 4), values: vec!(1.0f32, 0.5, 3.0) },
+        ];
+        for data in &invalid {
+            assert!(Value::try_sequence_from_csc(&var.shape(), 2, data, DeviceDescriptor::cpu()).is_err());
+        }
+        let empty = SparseCsc { col_starts: vec!(0), row_indices: vec!(), values: vec!(0f32; 0) };
+        assert!(Value::try_from_csc(&Shape::new(vec!(0, 2)), &empty, DeviceDescriptor::cpu()).is_err());
+    }
+
     #[test]
     fn test_sequence_batch_one_hot() {
         let var = Variable::sparse_input_variable(&Shape::new(vec!(10)));
@@ -628,6 +1324,7 @@ mod tests {
         let output = times(&var2, &var);
 
         let val = Value::batch_of_one_hot_sequences(&var.shape(), &vec!(vec!(1, 3), vec!(2, 4, 0)), DeviceDescriptor::cpu());
+        assert_eq!(val.to_one_hot_sequences(), vec!(vec!(1, 3), vec!(2, 4, 0)));
 
         let datamap = datamap!{var => &val};
         let mut outdatamap = outdatamap!{&output};
@@ -672,6 +1369,21 @@ mod tests {
 
             let result_array = outdatamap.get(&output).unwrap().to_ndarray();
             assert_eq!(result_array, array![[[8., 8.], [10., 10.], [0., 0.], [0., 0.]], [[12., 12.], [14., 14.], [16., 16.], [18., 18.]]].into_dyn());
+
+            let result_seqs = outdatamap.get(&output).unwrap().to_sequences();
+            assert_eq!(result_seqs, vec!(array![[8., 8.], [10., 10.]].into_dyn(), array![[12., 12.], [14., 14.], [16., 16.], [18., 18.]].into_dyn()));
+            assert_eq!(outdatamap.get(&output).unwrap().to_one_hot_sequences(), vec!(vec!(0, 0), vec!(0, 0, 0, 0)));
+        }
+
+        {
+            let val = Value::batch_of_sequences_from_vec_with_start_flags(&var.shape(), &vec!(vec!(1f32, 1., 2., 1., 1., 3.), vec!(1., 1., 4., 1., 1., 5., 1., 1., 6., 1., 1., 7.)), &[true, false], DeviceDescriptor::cpu());
+
+            assert_eq!(val.num_sequences(), 2);
+            assert_eq!(val.sequence_lengths(), vec!(2, 4));
+            assert_eq!(val.sequence_start_flags(), vec!(true, false));
+            assert_eq!(val.mask(), vec!(vec!(MaskKind::SequenceBegin, MaskKind::Valid, MaskKind::Invalid, MaskKind::Invalid),
+                                        vec!(MaskKind::Valid, MaskKind::Valid, MaskKind::Valid, MaskKind::Valid)));
+            assert_eq!(val.try_mask().unwrap(), val.mask());
         }
     }
 
@@ -682,4 +1394,30 @@ mod tests {
         let var2 = Variable::input_variable(&Shape::new(vec!(23,25)));
         let _failed_times = times(var, var2);
     }
+
+    #[test]
+    fn fail_try_times() {
+        let var = Variable::input_variable_with_name(&Shape::new(vec!(42,47)), "A");
+        let var2 = Variable::input_variable_with_name(&Shape::new(vec!(23,25)), "B");
+        let err = try_times(&var, &var2).unwrap_err();
+        assert_eq!(err.call(), "Times");
+        assert_eq!(err.variables(), &["A".to_owned(), "B".to_owned()]);
+        assert!(!err.message().is_empty());
+    }
+
+    #[test]
+    fn fail_try_slice_and_copy() {
+        let var = Variable::input_variable(&Shape::new(vec!(3)));
+        let err = try_slice(&var, &[&Axis::new(0)], &[0, 1], &[1]).unwrap_err();
+        assert_eq!(err.call(), "Slice");
+
+        let val = Value::from_vec(&var.shape(), &[1f32, 2., 3.], DeviceDescriptor::cpu());
+        assert!(val.try_copy_to_slice(&mut [0f32; 2]).is_err());
+        assert!(val.try_copy_to_slice(&mut [0f64; 3]).is_err());
+        assert!(val.try_to_csc_of::<f32>().is_err());
+        assert!(val.try_copy_to_ndarray(&mut ::ndarray::Array::<f32, _>::zeros((2, 2))).is_err());
+        assert!(val.data().try_copy_to_ndarray(&mut ::ndarray::Array::<f64, _>::zeros(3)).is_err());
+        assert_eq!(Value::try_from_nd_array_view(&val.data().try_deep_clone(false).unwrap()).unwrap().to_vec(), vec!(1., 2., 3.));
+        assert_eq!(val.data().try_alias(true).unwrap().to_vec(), vec!(1., 2., 3.));
+    }
 }
@@ -0,0 +1,300 @@
+use shape::{Shape, ShapeInner};
+use device::DeviceDescriptor;
+use data_type::{DataType, CntkElement};
+use value::{check_ndarray, ndarray_data};
+use ndarray::ShapeBuilder;
+use error::{Error, check_error};
+use std::ptr;
+use std::os::raw::{c_char, c_void};
+use ndarray::{Array, ArrayD, ArrayBase, Data, DataMut, Dimension};
+
+cpp! {{
+  #include <CNTKLibrary.h>
+  #include <cstdio>
+  #include <vector>
+
+  using namespace CNTK;
+  using namespace std;
+}}
+
+pub(super) type NDArrayViewInner = [u64; 2usize];
+
+/// Axis order and memory layout of ndarrays holding CNTK data.
+///
+/// CNTK stores data in column-major order (first axis changes fastest), ndarray defaults to row-major order
+/// (last axis changes fastest). Both layouts below describe the same memory without reordering it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NDArrayLayout {
+    /// Axes in reversed CNTK order, row-major memory order. Used by `to_ndarray` and expected by `*_from_ndarray`
+    /// (e.g. CNTK shape [3, 2] with sequence and batch axes becomes ndarray shape [batch, sequence, 2, 3]).
+    RowMajorReversed,
+    /// Axes in CNTK order, column-major (Fortran) memory order. `.t()` of such ndarray is `RowMajorReversed` one.
+    ColumnMajor,
+}
+
+/// Multidimensional data stored by CNTK (on CPU or GPU). Cloning only creates new reference to the same data,
+/// use `deep_clone` to copy the data.
+#[derive(Debug)]
+pub struct NDArrayView {
+    pub(super) payload: NDArrayViewInner
+}
+
+impl NDArrayView {
+    /// Creates NDArrayView with copy of the data (in CNTK column-major order).
+    pub fn from_slice<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> NDArrayView {
+        NDArrayView::try_from_slice(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_slice<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Result<NDArrayView, Error> {
+        let data_ptr = data.as_ptr() as *const c_void;
+        let data_size = data.len();
+        let dtype = T::data_type();
+        let shape_payload = shape.payload;
+        let device_payload = device.payload;
+        let payload = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", data_ptr as "const void*", data_size as "size_t", dtype as "DataType", device_payload as "DeviceDescriptor", mut error_p as "char*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return cntk_rs_guard(error_p, [&]() -> NDArrayViewPtr {
+                    auto view = MakeSharedObject<NDArrayView>(dtype, shape_payload, const_cast<void*>(data_ptr), data_size * DataTypeSize(dtype), device_payload, true);
+                    return view->DeepClone();
+                });
+            });
+            check_error(error_p, "NDArrayView::NDArrayView", &[])?;
+            payload
+        };
+        Ok(NDArrayView { payload })
+    }
+
+    /// Creates NDArrayView with copy of the data. Shape of ndarray must be reversed `shape`.
+    pub fn from_ndarray<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> NDArrayView {
+        NDArrayView::try_from_ndarray(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_ndarray<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<NDArrayView, Error> {
+        check_ndarray(shape, data, true, "NDArrayView::from_ndarray")?;
+        NDArrayView::try_from_slice(shape, &ndarray_data(data), device)
+    }
+
+    pub fn shape(&self) -> Shape {
+        let payload = self.payload;
+        Shape { payload: unsafe {
+            cpp!([payload as "NDArrayViewPtr"] -> ShapeInner as "NDShape" {
+                return payload->Shape();
+            })
+        }}
+    }
+
+    pub fn data_type(&self) -> DataType {
+        let payload = self.payload;
+        DataType::from_raw(unsafe {
+            cpp!([payload as "NDArrayViewPtr"] -> u32 as "unsigned int" {
+                return (unsigned int)payload->GetDataType();
+            })
+        })
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "NDArrayViewPtr"] -> bool as "bool" {
+                return payload->IsReadOnly();
+            })
+        }
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "NDArrayViewPtr"] -> bool as "bool" {
+                return payload->IsSparse();
+            })
+        }
+    }
+
+    /// Copies the data into new NDArrayView.
+    pub fn deep_clone(&self, read_only: bool) -> NDArrayView {
+        self.try_deep_clone(read_only).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_deep_clone(&self, read_only: bool) -> Result<NDArrayView, Error> {
+        let payload = self.payload;
+        Ok(NDArrayView { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "NDArrayViewPtr", read_only as "bool", mut error_p as "char*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return cntk_rs_guard(error_p, [&] { return payload->DeepClone(read_only); });
+            });
+            check_error(error_p, "NDArrayView::DeepClone", &[])?;
+            payload
+        }})
+    }
+
+    /// Creates new NDArrayView sharing the data with this one (read-only alias prevents writes through it).
+    pub fn alias(&self, read_only: bool) -> NDArrayView {
+        self.try_alias(read_only).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_alias(&self, read_only: bool) -> Result<NDArrayView, Error> {
+        let payload = self.payload;
+        Ok(NDArrayView { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "NDArrayViewPtr", read_only as "bool", mut error_p as "char*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return cntk_rs_guard(error_p, [&] { return payload->Alias(read_only); });
+            });
+            check_error(error_p, "NDArrayView::Alias", &[])?;
+            payload
+        }})
+    }
+
+    /// Creates view of part of the data (without copying) starting at `start_offset` with size `extent`
+    /// (both in CNTK axis order).
+    pub fn slice_view(&self, start_offset: &[usize], extent: &[usize], read_only: bool) -> NDArrayView {
+        self.try_slice_view(start_offset, extent, read_only).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_slice_view(&self, start_offset: &[usize], extent: &[usize], read_only: bool) -> Result<NDArrayView, Error> {
+        let payload = self.payload;
+        let start_ptr = start_offset.as_ptr();
+        let start_len = start_offset.len();
+        let extent_ptr = extent.as_ptr();
+        let extent_len = extent.len();
+        let payload = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "NDArrayViewPtr", start_ptr as "size_t*", start_len as "size_t", extent_ptr as "size_t*", extent_len as "size_t", read_only as "bool", mut error_p as "char*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return cntk_rs_guard(error_p, [&] { return payload->SliceView(vector<size_t>(start_ptr, start_ptr + start_len), vector<size_t>(extent_ptr, extent_ptr + extent_len), read_only); });
+            });
+            check_error(error_p, "NDArrayView::SliceView", &[])?;
+            payload
+        };
+        Ok(NDArrayView { payload })
+    }
+
+    /// Copies data of `source` (which must have the same shape) into this NDArrayView.
+    pub fn copy_from(&mut self, source: &NDArrayView) {
+        self.try_copy_from(source).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_copy_from(&mut self, source: &NDArrayView) -> Result<(), Error> {
+        let payload = self.payload;
+        let spayload = source.payload;
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            cpp!([payload as "NDArrayViewPtr", spayload as "NDArrayViewPtr", mut error_p as "char*"] {
+                cntk_rs_guard(error_p, [&] { payload->CopyFrom(*spayload); });
+            });
+            check_error(error_p, "NDArrayView::CopyFrom", &[])
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.to_vec_of::<f32>()
+    }
+
+    /// Copies data out of the NDArrayView. Panics if `T` does not match the data type of the NDArrayView.
+    pub fn to_vec_of<T: CntkElement>(&self) -> Vec<T> {
+        self.try_to_vec_of::<T>().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_to_vec(&self) -> Result<Vec<f32>, Error> {
+        self.try_to_vec_of::<f32>()
+    }
+
+    pub fn try_to_vec_of<T: CntkElement>(&self) -> Result<Vec<T>, Error> {
+        let mut buffer = vec![T::default(); self.shape().try_total_size()?];
+        self.try_copy_to_slice(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Copies data into `output`, which must have the same size. Panics if `T` does not match the data type of the NDArrayView.
+    pub fn copy_to_slice<T: CntkElement>(&self, output: &mut [T]) {
+        self.try_copy_to_slice(output).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_copy_to_slice<T: CntkElement>(&self, output: &mut [T]) -> Result<(), Error> {
+        if self.data_type() != T::data_type() {
+            return Err(Error::new("NDArrayView data type does not match requested element type", "NDArrayView::copy_to_slice", &[]));
+        }
+        let total_size = self.shape().try_total_size()?;
+        if output.len() != total_size {
+            return Err(Error::new(&format!("Output size {} does not match NDArrayView size {}", output.len(), total_size), "NDArrayView::copy_to_slice", &[]));
+        }
+        let payload = self.payload;
+        let dtype = T::data_type();
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let data: *const c_void = cpp!([payload as "NDArrayViewPtr", dtype as "DataType", mut error_p as "char*"] -> *const c_void as "const void*" {
+                return cntk_rs_guard(error_p, [&]() -> const void* {
+                    if (dtype == DataType::Double) {
+                        return payload->DataBuffer<double>();
+                    }
+                    return payload->DataBuffer<float>();
+                });
+            });
+            check_error(error_p, "NDArrayView::DataBuffer", &[])?;
+            ptr::copy(data as *const T, output.as_mut_ptr(), total_size);
+        }
+        Ok(())
+    }
+
+    /// Copies data into ndarray (with any memory layout) with reversed shape of the NDArrayView.
+    pub fn copy_to_ndarray<T: CntkElement, D: Dimension, S: DataMut<Elem=T>>(&self, output: &mut ArrayBase<S, D>) {
+        self.try_copy_to_ndarray(output).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_copy_to_ndarray<T: CntkElement, D: Dimension, S: DataMut<Elem=T>>(&self, output: &mut ArrayBase<S, D>) -> Result<(), Error> {
+        check_ndarray(&self.shape(), output, true, "NDArrayView::copy_to_ndarray")?;
+        if let Some(slice) = output.as_slice_mut() {
+            return self.try_copy_to_slice(slice);
+        }
+        for (x, y) in output.iter_mut().zip(self.try_to_vec_of::<T>()?) {
+            *x = y;
+        }
+        Ok(())
+    }
+
+    pub fn to_ndarray(&self) -> ArrayD<f32> {
+        self.to_ndarray_of::<f32>()
+    }
+
+    pub fn to_ndarray_of<T: CntkElement>(&self) -> ArrayD<T> {
+        self.to_ndarray_with_layout::<T>(NDArrayLayout::RowMajorReversed)
+    }
+
+    pub fn try_to_ndarray_of<T: CntkElement>(&self) -> Result<ArrayD<T>, Error> {
+        self.try_to_ndarray_with_layout::<T>(NDArrayLayout::RowMajorReversed)
+    }
+
+    pub fn to_ndarray_with_layout<T: CntkElement>(&self, layout: NDArrayLayout) -> ArrayD<T> {
+        self.try_to_ndarray_with_layout::<T>(layout).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_to_ndarray_with_layout<T: CntkElement>(&self, layout: NDArrayLayout) -> Result<ArrayD<T>, Error> {
+        let vec = self.try_to_vec_of::<T>()?;
+        Ok(match layout {
+            NDArrayLayout::RowMajorReversed => Array::from_shape_vec(self.shape().to_vec_reversed(), vec).unwrap(),
+            NDArrayLayout::ColumnMajor => Array::from_shape_vec(self.shape().to_vec().f(), vec).unwrap(),
+        })
+    }
+}
+
+impl Clone for NDArrayView {
+    fn clone(&self) -> Self {
+        let xpayload = self.payload;
+        let payload = unsafe {
+            cpp!([xpayload as "NDArrayViewPtr"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return xpayload;
+            })
+        };
+        NDArrayView {payload}
+    }
+}
+
+impl Drop for NDArrayView {
+    fn drop(&mut self) {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "NDArrayViewPtr"] {
+                payload.~NDArrayViewPtr();
+            })
+        };
+    }
+}
@@ -1,10 +1,12 @@
-use variable::{Variable, VariableInner};
+use variable::{Variable, VariableInner, Constant};
 use function::{Function, FunctionInner};
 use axis::Axis;
 use shape::Shape;
 use std::borrow::Borrow;
 use std::ptr;
-use std::ffi::CStr;
+use std::os::raw::c_char;
+use error::{Error, check_error};
+use data_type::DataType;
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -17,956 +19,726 @@ cpp! {{
 }}
 
 pub fn transpose_axes<T: Into<Variable>>(x: T, axis1: &Axis, axis2: &Axis) -> Function {
+    try_transpose_axes(x, axis1, axis2).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_transpose_axes<T: Into<Variable>>(x: T, axis1: &Axis, axis2: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let a1payload = axis1.payload;
     let a2payload = axis2.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", a1payload as "Axis", a2payload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return TransposeAxes(xpayload, a1payload, a2payload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return TransposeAxes(xpayload, a1payload, a2payload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "TransposeAxes", &[&xv])?;
         payload
     };
-    Function { payload }
+    Ok(Function { payload })
 }
 
 pub fn dropout<T: Into<Variable>>(x: T, dropout_rate: f64) -> Function {
+    try_dropout(x, dropout_rate).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_dropout<T: Into<Variable>>(x: T, dropout_rate: f64) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", dropout_rate as "double", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Dropout(xpayload, dropout_rate);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Dropout(xpayload, dropout_rate); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Dropout", &[&xv])?;
         payload
     };
-    Function { payload }
+    Ok(Function { payload })
 }
 
 // TODO: Make this more friendly
-pub fn splice(variables: &[&Variable], axis: &Axis) -> Function {
-    let data: Vec<Variable> = variables.iter().map(|&x| x.clone()).collect();
+pub fn splice<T: Borrow<Variable>>(variables: &[T], axis: &Axis) -> Function {
+    try_splice(variables, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_splice<T: Borrow<Variable>>(variables: &[T], axis: &Axis) -> Result<Function, Error> {
+    let data: Vec<Variable> = variables.iter().map(|x| x.borrow().clone()).collect();
     let data_ptr = data.as_ptr();
     let data_size = data.len();
     let apayload = axis.payload;
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([data_ptr as "Variable*", data_size as "size_t", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Splice(vector<Variable>(data_ptr, data_ptr + data_size), apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Splice(vector<Variable>(data_ptr, data_ptr + data_size), apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Splice", &data.iter().collect::<Vec<_>>())?;
         payload
-    }}
+    }})
 }
 
 pub fn reshape<T: Into<Variable>>(x: T, shape: &Shape) -> Function {
+    try_reshape(x, shape).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reshape<T: Into<Variable>>(x: T, shape: &Shape) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let spayload = shape.payload;
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", spayload as "NDShape", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Reshape(xpayload, spayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Reshape(xpayload, spayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Reshape", &[&xv])?;
         payload
-    }}
+    }})
 }
 
 pub fn slice<T: Into<Variable>>(x: T, axis: &[&Axis], begin_index: &[i32], end_index: &[i32]) -> Function {
+    try_slice(x, axis, begin_index, end_index).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_slice<T: Into<Variable>>(x: T, axis: &[&Axis], begin_index: &[i32], end_index: &[i32]) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
-    assert_eq!(axis.len(), begin_index.len());
-    assert_eq!(axis.len(), end_index.len());
+    if axis.len() != begin_index.len() || axis.len() != end_index.len() {
+        return Err(Error::new(&format!("Number of axes {} does not match number of begin indices {} and end indices {}", axis.len(), begin_index.len(), end_index.len()), "Slice", &[&xv]));
+    }
     let len = axis.len();
     let adata: Vec<Axis> = axis.iter().map(|&x| x.clone()).collect();
     let adata_ptr = adata.as_ptr();
     let bdata_ptr = begin_index.as_ptr();
     let edata_ptr = end_index.as_ptr();
 
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", adata_ptr as "Axis*", len as "size_t", bdata_ptr as "int*", edata_ptr as "int*", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
                 return Slice(xpayload,
                              vector<Axis>(adata_ptr, adata_ptr + len),
                              vector<int>(bdata_ptr, bdata_ptr + len),
                              vector<int>(edata_ptr, edata_ptr + len));
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Slice", &[&xv])?;
         payload
-    }}
+    }})
 }
 
 pub fn named_alias<T: Into<Variable>>(x: T, name: &str) -> Function {
+    try_named_alias(x, name).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_named_alias<T: Into<Variable>>(x: T, name: &str) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let name_ptr = name.as_ptr();
     let name_len = name.len();
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", name_ptr as "char*", name_len as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try{
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
                 string name(name_ptr, name_ptr + name_len);
                 wstring wname;
                 wname.assign(name.begin(), name.end());
                 return Alias(xpayload, wname);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Alias", &[&xv])?;
         payload
-    }}
+    }})
 }
 
 pub fn past_value<T: Into<Variable>>(x: T) -> Function {
+    try_past_value(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_past_value<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return PastValue(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return PastValue(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "PastValue", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn future_value<T: Into<Variable>>(x: T) -> Function {
+    try_future_value(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_future_value<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return FutureValue(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return FutureValue(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "FutureValue", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn past_value_with_init<T: Into<Variable>, U: Into<Variable>>(x: T, initial: U) -> Function {
+    try_past_value_with_init(x, initial).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_past_value_with_init<T: Into<Variable>, U: Into<Variable>>(x: T, initial: U) -> Result<Function, Error> {
     let xv = x.into();
     let iv = initial.into();
     let xpayload = xv.payload;
     let ipayload = iv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ipayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return PastValue(xpayload, ipayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return PastValue(xpayload, ipayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "PastValue", &[&xv, &iv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn future_value_with_init<T: Into<Variable>, U: Into<Variable>>(x: T, initial: U) -> Function {
+    try_future_value_with_init(x, initial).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_future_value_with_init<T: Into<Variable>, U: Into<Variable>>(x: T, initial: U) -> Result<Function, Error> {
     let xv = x.into();
     let iv = initial.into();
     let xpayload = xv.payload;
     let ipayload = iv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ipayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return FutureValue(xpayload, ipayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return FutureValue(xpayload, ipayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "FutureValue", &[&xv, &iv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn first<T: Into<Variable>>(x: T) -> Function {
+    try_first(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_first<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sequence::First(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sequence::First(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sequence::First", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn last<T: Into<Variable>>(x: T) -> Function {
+    try_last(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_last<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sequence::Last(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sequence::Last(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sequence::Last", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 /* unary ops begin here */
 
 
 pub fn negate<T: Into<Variable>>(x: T) -> Function {
+    try_negate(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_negate<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Negate(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Negate(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Negate", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn sigmoid<T: Into<Variable>>(x: T) -> Function {
+    try_sigmoid(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_sigmoid<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sigmoid(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sigmoid(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sigmoid", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn tanh<T: Into<Variable>>(x: T) -> Function {
+    try_tanh(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_tanh<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Tanh(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Tanh(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Tanh", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn asin<T: Into<Variable>>(x: T) -> Function {
+    try_asin(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_asin<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Asin(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Asin(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Asin", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn sin<T: Into<Variable>>(x: T) -> Function {
+    try_sin(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_sin<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sin(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sin(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sin", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn acos<T: Into<Variable>>(x: T) -> Function {
+    try_acos(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_acos<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Acos(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Acos(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Acos", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn cos<T: Into<Variable>>(x: T) -> Function {
+    try_cos(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_cos<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Cos(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Cos(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Cos", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn cosh<T: Into<Variable>>(x: T) -> Function {
+    try_cosh(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_cosh<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Cosh(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Cosh(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Cosh", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn sinh<T: Into<Variable>>(x: T) -> Function {
+    try_sinh(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_sinh<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sinh(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sinh(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sinh", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn relu<T: Into<Variable>>(x: T) -> Function {
+    try_relu(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_relu<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReLU(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReLU(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReLU", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn exp<T: Into<Variable>>(x: T) -> Function {
+    try_exp(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_exp<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Exp(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Exp(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Exp", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn log<T: Into<Variable>>(x: T) -> Function {
+    try_log(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_log<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Log(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Log(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Log", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn square<T: Into<Variable>>(x: T) -> Function {
+    try_square(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_square<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Square(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Square(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Square", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn sqrt<T: Into<Variable>>(x: T) -> Function {
+    try_sqrt(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_sqrt<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sqrt(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sqrt(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sqrt", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn round<T: Into<Variable>>(x: T) -> Function {
+    try_round(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_round<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Round(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Round(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Round", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn floor<T: Into<Variable>>(x: T) -> Function {
+    try_floor(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_floor<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Floor(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Floor(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Floor", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn ceil<T: Into<Variable>>(x: T) -> Function {
+    try_ceil(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_ceil<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Ceil(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Ceil(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Ceil", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn abs<T: Into<Variable>>(x: T) -> Function {
+    try_abs(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_abs<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Abs(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Abs(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Abs", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reciprocal<T: Into<Variable>>(x: T) -> Function {
+    try_reciprocal(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reciprocal<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Reciprocal(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Reciprocal(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Reciprocal", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn softmax<T: Into<Variable>>(x: T) -> Function {
+    try_softmax(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_softmax<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Softmax(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Softmax(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Softmax", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn hardmax<T: Into<Variable>>(x: T) -> Function {
+    try_hardmax(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_hardmax<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Hardmax(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Hardmax(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Hardmax", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn transpose<T: Into<Variable>>(x: T) -> Function {
+    try_transpose(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_transpose<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Transpose(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Transpose(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Transpose", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn to_batch<T: Into<Variable>>(x: T) -> Function {
+    try_to_batch(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_to_batch<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ToBatch(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ToBatch(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ToBatch", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn alias<T: Into<Variable>>(x: T) -> Function {
+    try_alias(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_alias<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Alias(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Alias(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Alias", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn stop_gradient<T: Into<Variable>>(x: T) -> Function {
+    try_stop_gradient(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_stop_gradient<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return StopGradient(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return StopGradient(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "StopGradient", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn elu<T: Into<Variable>>(x: T) -> Function {
+    try_elu(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_elu<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ELU(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ELU(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ELU", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 /*pub fn leaky_relu<T: Into<Variable>>(x: T) -> Function {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return LeakyReLU(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return LeakyReLU(xpayload); });
         });
         if !error_p.is_null() {
              let msg = CStr::from_ptr(error_p).to_str().unwrap();
@@ -978,27 +750,21 @@ pub fn elu<T: Into<Variable>>(x: T) -> Function {
 }*/
 
 pub fn softplus<T: Into<Variable>>(x: T) -> Function {
+    try_softplus(x).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_softplus<T: Into<Variable>>(x: T) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Softplus(xpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Softplus(xpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Softplus", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 
@@ -1007,318 +773,250 @@ pub fn softplus<T: Into<Variable>>(x: T) -> Function {
 /* binary ops begin here */
 
 pub fn plus<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_plus(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_plus<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Plus(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Plus(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Plus", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn minus<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_minus(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_minus<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Minus(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Minus(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Minus", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn log_add_exp<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_log_add_exp(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_log_add_exp<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return LogAddExp(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return LogAddExp(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "LogAddExp", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn pow<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_pow(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_pow<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Pow(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Pow(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Pow", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn element_times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_element_times(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_element_times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ElementTimes(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ElementTimes(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ElementTimes", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn element_divide<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_element_divide(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_element_divide<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ElementDivide(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ElementDivide(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ElementDivide", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_equal(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Equal(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Equal(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Equal", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn not_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_not_equal(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_not_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return NotEqual(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return NotEqual(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "NotEqual", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn less<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_less(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_less<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Less(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Less(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Less", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn less_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_less_equal(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_less_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return LessEqual(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return LessEqual(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "LessEqual", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn greater<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_greater(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_greater<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Greater(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Greater(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Greater", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn greater_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_greater_equal(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_greater_equal<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return GreaterEqual(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return GreaterEqual(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "GreaterEqual", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_times(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
@@ -1326,178 +1024,132 @@ pub fn times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
     let payload = unsafe {
         let mut error_p : *mut i8 = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Times(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Times(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-            let msg = CStr::from_ptr(error_p).to_str().unwrap();
-            panic!("{}", msg);
-        }
+        check_error(error_p, "Times", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn transpose_times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_transpose_times(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_transpose_times<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return TransposeTimes(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return TransposeTimes(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "TransposeTimes", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn cosine_distance<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_cosine_distance(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_cosine_distance<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return CosineDistance(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return CosineDistance(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "CosineDistance", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn binary_cross_entropy<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_binary_cross_entropy(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_binary_cross_entropy<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return BinaryCrossEntropy(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return BinaryCrossEntropy(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "BinaryCrossEntropy", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn squared_error<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_squared_error(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_squared_error<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return SquaredError(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return SquaredError(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "SquaredError", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn cross_entropy_with_softmax<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_cross_entropy_with_softmax(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_cross_entropy_with_softmax<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return CrossEntropyWithSoftmax(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return CrossEntropyWithSoftmax(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "CrossEntropyWithSoftmax", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn classification_error<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_classification_error(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_classification_error<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let ypayload: VariableInner = yv.borrow().payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ClassificationError(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ClassificationError(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ClassificationError", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 
@@ -1507,228 +1159,174 @@ pub fn classification_error<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) ->
 
 
 pub fn softmax_with_axis<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_softmax_with_axis(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_softmax_with_axis<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Softmax(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Softmax(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Softmax", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reduce_sum<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_reduce_sum(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reduce_sum<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReduceSum(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReduceSum(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReduceSum", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reduce_log_sum<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_reduce_log_sum(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reduce_log_sum<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReduceLogSum(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReduceLogSum(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReduceLogSum", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reduce_mean<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_reduce_mean(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reduce_mean<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReduceMean(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReduceMean(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReduceMean", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reduce_max<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_reduce_max(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reduce_max<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReduceMax(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReduceMax(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReduceMax", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reduce_min<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_reduce_min(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reduce_min<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReduceMin(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReduceMin(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReduceMin", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn reduce_prod<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_reduce_prod(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_reduce_prod<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ReduceProd(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ReduceProd(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ReduceProd", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn argmax<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_argmax(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_argmax<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Argmax(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Argmax(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Argmax", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn argmin<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
+    try_argmin(x, axis).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_argmin<T: Into<Variable>>(x: T, axis: &Axis) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.borrow().payload;
     let apayload = axis.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", apayload as "Axis", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Argmin(xpayload, apayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Argmin(xpayload, apayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Argmin", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 
@@ -1737,182 +1335,537 @@ pub fn argmin<T: Into<Variable>>(x: T, axis: &Axis) -> Function {
 
 /* random ops */
 pub fn normal_random_like<T: Into<Variable>>(x: T, mean: f64, scale: f64) -> Function {
+    try_normal_random_like(x, mean, scale).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_normal_random_like<T: Into<Variable>>(x: T, mean: f64, scale: f64) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mean as "double", scale as "double", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return NormalRandomLike(xpayload, mean, scale);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return NormalRandomLike(xpayload, mean, scale); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "NormalRandomLike", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn bernoulli_random_like<T: Into<Variable>>(x: T, mean: f64) -> Function {
+    try_bernoulli_random_like(x, mean).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_bernoulli_random_like<T: Into<Variable>>(x: T, mean: f64) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", mean as "double", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return BernoulliRandomLike(xpayload, mean);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return BernoulliRandomLike(xpayload, mean); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "BernoulliRandomLike", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn uniform_random_like<T: Into<Variable>>(x: T, low: f64, high: f64) -> Function {
+    try_uniform_random_like(x, low, high).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_uniform_random_like<T: Into<Variable>>(x: T, low: f64, high: f64) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", low as "double", high as "double", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return UniformRandomLike(xpayload, low, high);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return UniformRandomLike(xpayload, low, high); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "UniformRandomLike", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn gumbel_random_like<T: Into<Variable>>(x: T, loc: f64, scale: f64) -> Function {
+    try_gumbel_random_like(x, loc, scale).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_gumbel_random_like<T: Into<Variable>>(x: T, loc: f64, scale: f64) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", loc as "double", scale as "double", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return GumbelRandomLike(xpayload, loc, scale);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return GumbelRandomLike(xpayload, loc, scale); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "GumbelRandomLike", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 /* random ops end */
 
 /* convolution */
+
+/// Optional arguments of `convolution_with_options` and `convolution_transpose`. Per axis vectors (`sharing`,
+/// `auto_padding`) and shapes (`strides`, `dilation`) are broadcast over the remaining axes by CNTK.
+#[derive(Debug, Clone)]
+pub struct ConvolutionOptions {
+    strides: Shape,
+    sharing: Vec<bool>,
+    auto_padding: Vec<bool>,
+    padding: Option<(Shape, Shape)>,
+    dilation: Shape,
+    reduction_rank: usize,
+    groups: usize,
+    max_temp_mem_size: usize,
+    output_shape: Shape,
+}
+
+impl ConvolutionOptions {
+    pub fn new() -> ConvolutionOptions {
+        ConvolutionOptions {
+            strides: Shape::new(vec!(1)),
+            sharing: vec!(true),
+            auto_padding: vec!(true),
+            padding: None,
+            dilation: Shape::new(vec!(1)),
+            reduction_rank: 1,
+            groups: 1,
+            max_temp_mem_size: 0,
+            output_shape: Shape::new(vec!(0)),
+        }
+    }
+
+    pub fn strides(mut self, strides: &Shape) -> ConvolutionOptions {
+        self.strides = strides.clone();
+        self
+    }
+
+    pub fn sharing(mut self, sharing: &[bool]) -> ConvolutionOptions {
+        self.sharing = sharing.to_vec();
+        self
+    }
+
+    pub fn auto_padding(mut self, auto_padding: &[bool]) -> ConvolutionOptions {
+        self.auto_padding = auto_padding.to_vec();
+        self
+    }
+
+    /// Explicit zero padding before (`lower`) and after (`upper`) every axis of the operand, missing trailing
+    /// axes are not padded. Replaces auto padding (only supported by `convolution_with_options`).
+    pub fn padding(mut self, lower: &Shape, upper: &Shape) -> ConvolutionOptions {
+        self.padding = Some((lower.clone(), upper.clone()));
+        self
+    }
+
+    pub fn dilation(mut self, dilation: &Shape) -> ConvolutionOptions {
+        self.dilation = dilation.clone();
+        self
+    }
+
+    /// Number of trailing operand axes reduced by the kernel (1 for channel axis, 0 when operand has no channels).
+    pub fn reduction_rank(mut self, reduction_rank: usize) -> ConvolutionOptions {
+        self.reduction_rank = reduction_rank;
+        self
+    }
+
+    /// Number of groups of grouped convolution (only supported by `convolution_with_options`).
+    pub fn groups(mut self, groups: usize) -> ConvolutionOptions {
+        self.groups = groups;
+        self
+    }
+
+    /// Maximum size of workspace in samples (0 means no limit).
+    pub fn max_temp_mem_size(mut self, max_temp_mem_size: usize) -> ConvolutionOptions {
+        self.max_temp_mem_size = max_temp_mem_size;
+        self
+    }
+
+    /// Output shape of `convolution_transpose` (needed when strides make it ambiguous), by default inferred.
+    pub fn output_shape(mut self, output_shape: &Shape) -> ConvolutionOptions {
+        self.output_shape = output_shape.clone();
+        self
+    }
+}
+
+impl Default for ConvolutionOptions {
+    fn default() -> ConvolutionOptions {
+        ConvolutionOptions::new()
+    }
+}
+
 pub fn convolution<T: Into<Variable>, U: Into<Variable>>(convmap: T, y: U, strides: &Shape) -> Function {
+    try_convolution(convmap, y, strides).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_convolution<T: Into<Variable>, U: Into<Variable>>(convmap: T, y: U, strides: &Shape) -> Result<Function, Error> {
+    try_convolution_with_options(convmap, y, &ConvolutionOptions::new().strides(strides))
+}
+
+pub fn convolution_with_options<T: Into<Variable>, U: Into<Variable>>(convmap: T, y: U, options: &ConvolutionOptions) -> Function {
+    try_convolution_with_options(convmap, y, options).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_convolution_with_options<T: Into<Variable>, U: Into<Variable>>(convmap: T, y: U, options: &ConvolutionOptions) -> Result<Function, Error> {
     let convmapv = convmap.into();
     let convmappayload = convmapv.payload;
     let yv = y.into();
     let ypayload = yv.payload;
-    let spayload = strides.payload;
-    let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
-        let payload = cpp!([convmappayload as "Variable", ypayload as "Variable", spayload as "NDShape", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Convolution(convmappayload, ypayload, spayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
-        });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
-        payload
-    };
-    Function {payload}
+    let spayload = options.strides.payload;
+    let sharing_ptr = options.sharing.as_ptr();
+    let sharing_len = options.sharing.len();
+    let auto_padding_ptr = options.auto_padding.as_ptr();
+    let auto_padding_len = options.auto_padding.len();
+    let dpayload = options.dilation.payload;
+    let reduction_rank = options.reduction_rank;
+    let groups = options.groups;
+    let max_temp_mem_size = options.max_temp_mem_size;
+    let has_padding = options.padding.is_some();
+    let (lower, upper) = options.padding.clone().unwrap_or_else(|| (Shape::scalar(), Shape::scalar()));
+    let lpayload = lower.payload;
+    let upayload = upper.payload;
+    let payload = unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([convmappayload as "Variable", ypayload as "Variable", spayload as "NDShape", sharing_ptr as "const bool*", sharing_len as "size_t",
+                            auto_padding_ptr as "const bool*", auto_padding_len as "size_t", dpayload as "NDShape", reduction_rank as "size_t", groups as "size_t",
+                            max_temp_mem_size as "size_t", has_padding as "bool", lpayload as "NDShape", upayload as "NDShape", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                vector<bool> sharing(sharing_ptr, sharing_ptr + sharing_len);
+                vector<bool> auto_padding(auto_padding_ptr, auto_padding_ptr + auto_padding_len);
+                Variable operand = ypayload;
+                if (has_padding) {
+                    auto rank = operand.Shape().Rank();
+                    vector<size_t> head(lpayload.Dimensions());
+                    vector<size_t> foot(upayload.Dimensions());
+                    head.resize(rank, 0);
+                    foot.resize(rank, 0);
+                    operand = Pad(operand, PaddingMode::CONSTANTPAD, head, foot);
+                    auto_padding = {false};
+                }
+                return Convolution(convmappayload, operand, spayload, sharing, auto_padding, dpayload, reduction_rank, groups, max_temp_mem_size);
+            });
+        });
+        check_error(error_p, "Convolution", &[&convmapv, &yv])?;
+        payload
+    };
+    Ok(Function {payload})
+}
+
+/// Transposed convolution (deconvolution), `convmap` has the same layout as for `convolution` producing `y`.
+pub fn convolution_transpose<T: Into<Variable>, U: Into<Variable>>(convmap: T, y: U, options: &ConvolutionOptions) -> Function {
+    try_convolution_transpose(convmap, y, options).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_convolution_transpose<T: Into<Variable>, U: Into<Variable>>(convmap: T, y: U, options: &ConvolutionOptions) -> Result<Function, Error> {
+    let convmapv = convmap.into();
+    let yv = y.into();
+    if options.padding.is_some() || options.groups != 1 {
+        return Err(Error::new("Explicit padding and groups are not supported by transposed convolution", "ConvolutionTranspose", &[&convmapv, &yv]));
+    }
+    let convmappayload = convmapv.payload;
+    let ypayload = yv.payload;
+    let spayload = options.strides.payload;
+    let sharing_ptr = options.sharing.as_ptr();
+    let sharing_len = options.sharing.len();
+    let auto_padding_ptr = options.auto_padding.as_ptr();
+    let auto_padding_len = options.auto_padding.len();
+    let opayload = options.output_shape.payload;
+    let dpayload = options.dilation.payload;
+    let reduction_rank = options.reduction_rank;
+    let max_temp_mem_size = options.max_temp_mem_size;
+    let payload = unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([convmappayload as "Variable", ypayload as "Variable", spayload as "NDShape", sharing_ptr as "const bool*", sharing_len as "size_t",
+                            auto_padding_ptr as "const bool*", auto_padding_len as "size_t", opayload as "NDShape", dpayload as "NDShape", reduction_rank as "size_t",
+                            max_temp_mem_size as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                vector<bool> sharing(sharing_ptr, sharing_ptr + sharing_len);
+                vector<bool> auto_padding(auto_padding_ptr, auto_padding_ptr + auto_padding_len);
+                return ConvolutionTranspose(convmappayload, ypayload, spayload, sharing, auto_padding, opayload, dpayload, reduction_rank, max_temp_mem_size);
+            });
+        });
+        check_error(error_p, "ConvolutionTranspose", &[&convmapv, &yv])?;
+        payload
+    };
+    Ok(Function {payload})
+}
+
+/// Mirrors CNTK PoolingType enum
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolingType {
+    Max = 0,
+    Average = 1,
+}
+
+/// Optional arguments of `pooling` and `unpooling` (which uses only strides and auto padding).
+#[derive(Debug, Clone)]
+pub struct PoolingOptions {
+    strides: Shape,
+    auto_padding: Vec<bool>,
+    ceil_out_dim: bool,
+    include_pad: bool,
+}
+
+impl PoolingOptions {
+    pub fn new() -> PoolingOptions {
+        PoolingOptions {
+            strides: Shape::new(vec!(1)),
+            auto_padding: vec!(false),
+            ceil_out_dim: false,
+            include_pad: false,
+        }
+    }
+
+    pub fn strides(mut self, strides: &Shape) -> PoolingOptions {
+        self.strides = strides.clone();
+        self
+    }
+
+    pub fn auto_padding(mut self, auto_padding: &[bool]) -> PoolingOptions {
+        self.auto_padding = auto_padding.to_vec();
+        self
+    }
+
+    /// Rounds output dimensions up, so that the last partial window is also pooled.
+    pub fn ceil_out_dim(mut self, ceil_out_dim: bool) -> PoolingOptions {
+        self.ceil_out_dim = ceil_out_dim;
+        self
+    }
+
+    /// Counts padded elements in the denominator of average pooling.
+    pub fn include_pad(mut self, include_pad: bool) -> PoolingOptions {
+        self.include_pad = include_pad;
+        self
+    }
+}
+
+impl Default for PoolingOptions {
+    fn default() -> PoolingOptions {
+        PoolingOptions::new()
+    }
 }
 
 pub fn max_pooling<T: Into<Variable>>(x: T, window_shape: &Shape, strides: &Shape) -> Function {
+    try_max_pooling(x, window_shape, strides).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_max_pooling<T: Into<Variable>>(x: T, window_shape: &Shape, strides: &Shape) -> Result<Function, Error> {
+    try_pooling(x, PoolingType::Max, window_shape, &PoolingOptions::new().strides(strides))
+}
+
+pub fn avg_pooling<T: Into<Variable>>(x: T, window_shape: &Shape, strides: &Shape) -> Function {
+    try_avg_pooling(x, window_shape, strides).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_avg_pooling<T: Into<Variable>>(x: T, window_shape: &Shape, strides: &Shape) -> Result<Function, Error> {
+    try_pooling(x, PoolingType::Average, window_shape, &PoolingOptions::new().strides(strides))
+}
+
+pub fn pooling<T: Into<Variable>>(x: T, pooling_type: PoolingType, window_shape: &Shape, options: &PoolingOptions) -> Function {
+    try_pooling(x, pooling_type, window_shape, options).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_pooling<T: Into<Variable>>(x: T, pooling_type: PoolingType, window_shape: &Shape, options: &PoolingOptions) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let spayload = window_shape.payload;
-    let stpayload = strides.payload;
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
-        let payload = cpp!([xpayload as "Variable", spayload as "NDShape", stpayload as "NDShape", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Pooling(xpayload, PoolingType::Max, spayload, stpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+    let stpayload = options.strides.payload;
+    let auto_padding_ptr = options.auto_padding.as_ptr();
+    let auto_padding_len = options.auto_padding.len();
+    let ceil_out_dim = options.ceil_out_dim;
+    let include_pad = options.include_pad;
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([xpayload as "Variable", pooling_type as "PoolingType", spayload as "NDShape", stpayload as "NDShape", auto_padding_ptr as "const bool*",
+                            auto_padding_len as "size_t", ceil_out_dim as "bool", include_pad as "bool", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                vector<bool> auto_padding(auto_padding_ptr, auto_padding_ptr + auto_padding_len);
+                return Pooling(xpayload, pooling_type, spayload, stpayload, auto_padding, ceil_out_dim, include_pad);
+            });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Pooling", &[&xv])?;
         payload
-    }}
+    }})
 }
 
-pub fn avg_pooling<T: Into<Variable>>(x: T, window_shape: &Shape, strides: &Shape) -> Function {
+/// Max unpooling of `x` (output of max pooling of `pooling_input` with the same window and options),
+/// every value is placed at the position of the maximum in `pooling_input`, other positions are zero.
+pub fn unpooling<T: Into<Variable>, U: Into<Variable>>(x: T, pooling_input: U, window_shape: &Shape, options: &PoolingOptions) -> Function {
+    try_unpooling(x, pooling_input, window_shape, options).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_unpooling<T: Into<Variable>, U: Into<Variable>>(x: T, pooling_input: U, window_shape: &Shape, options: &PoolingOptions) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
+    let pv = pooling_input.into();
+    let ppayload = pv.payload;
     let spayload = window_shape.payload;
-    let stpayload = strides.payload;
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
-        let payload = cpp!([xpayload as "Variable", spayload as "NDShape", stpayload as "NDShape", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Pooling(xpayload, PoolingType::Average, spayload, stpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+    let stpayload = options.strides.payload;
+    let auto_padding_ptr = options.auto_padding.as_ptr();
+    let auto_padding_len = options.auto_padding.len();
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([xpayload as "Variable", ppayload as "Variable", spayload as "NDShape", stpayload as "NDShape", auto_padding_ptr as "const bool*",
+                            auto_padding_len as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                vector<bool> auto_padding(auto_padding_ptr, auto_padding_ptr + auto_padding_len);
+                return Unpooling(xpayload, ppayload, UnpoolingType::Max, spayload, stpayload, auto_padding);
+            });
+        });
+        check_error(error_p, "Unpooling", &[&xv, &pv])?;
+        payload
+    }})
+}
+
+/* normalization */
+
+/// Optional arguments of `batch_normalization`, defaults are the same as in CNTK Python layers library.
+#[derive(Debug, Clone)]
+pub struct BatchNormalizationOptions {
+    spatial: bool,
+    normalization_time_constant: f64,
+    blend_time_constant: f64,
+    epsilon: f64,
+    use_cudnn_engine: bool,
+}
+
+impl BatchNormalizationOptions {
+    pub fn new() -> BatchNormalizationOptions {
+        BatchNormalizationOptions {
+            spatial: false,
+            normalization_time_constant: 5000.,
+            blend_time_constant: 0.,
+            epsilon: 0.00001,
+            use_cudnn_engine: true,
+        }
+    }
+
+    /// Shares statistics over all spatial positions (for convolutional layers, scale and statistics have
+    /// shape of the channel axis).
+    pub fn spatial(mut self, spatial: bool) -> BatchNormalizationOptions {
+        self.spatial = spatial;
+        self
+    }
+
+    /// Time constant (in samples) of running average of statistics, 0 keeps statistics of the last minibatch
+    /// only and infinity computes cumulative average.
+    pub fn normalization_time_constant(mut self, normalization_time_constant: f64) -> BatchNormalizationOptions {
+        self.normalization_time_constant = normalization_time_constant;
+        self
+    }
+
+    /// Time constant (in samples) for blending running statistics into minibatch statistics during training,
+    /// infinity uses running statistics only.
+    pub fn blend_time_constant(mut self, blend_time_constant: f64) -> BatchNormalizationOptions {
+        self.blend_time_constant = blend_time_constant;
+        self
+    }
+
+    pub fn epsilon(mut self, epsilon: f64) -> BatchNormalizationOptions {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn use_cudnn_engine(mut self, use_cudnn_engine: bool) -> BatchNormalizationOptions {
+        self.use_cudnn_engine = use_cudnn_engine;
+        self
+    }
+}
+
+impl Default for BatchNormalizationOptions {
+    fn default() -> BatchNormalizationOptions {
+        BatchNormalizationOptions::new()
+    }
+}
+
+/// Batch normalization. Running statistics are constants updated in place: when function is run for training
+/// (by `Trainer` or `Function::forward` retaining backward state) minibatch statistics are used and running
+/// ones are updated, `Function::evaluate` normalizes with running statistics. `running_count` is a scalar
+/// constant (initially 0).
+pub fn batch_normalization<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, scale: U, bias: V, running_mean: &Constant, running_variance: &Constant,
+                                                                                     running_count: &Constant, options: &BatchNormalizationOptions) -> Function {
+    try_batch_normalization(x, scale, bias, running_mean, running_variance, running_count, options).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_batch_normalization<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, scale: U, bias: V, running_mean: &Constant, running_variance: &Constant,
+                                                                                         running_count: &Constant, options: &BatchNormalizationOptions) -> Result<Function, Error> {
+    let xv = x.into();
+    let xpayload = xv.payload;
+    let sv = scale.into();
+    let spayload = sv.payload;
+    let bv = bias.into();
+    let bpayload = bv.payload;
+    let mpayload = running_mean.payload;
+    let vpayload = running_variance.payload;
+    let cpayload = running_count.payload;
+    let spatial = options.spatial;
+    let normalization_time_constant = options.normalization_time_constant;
+    let blend_time_constant = options.blend_time_constant;
+    let epsilon = options.epsilon;
+    let use_cudnn_engine = options.use_cudnn_engine;
+    let payload = unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([xpayload as "Variable", spayload as "Variable", bpayload as "Variable", mpayload as "Variable", vpayload as "Variable", cpayload as "Variable",
+                            spatial as "bool", normalization_time_constant as "double", blend_time_constant as "double", epsilon as "double", use_cudnn_engine as "bool",
+                            mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&] {
+                return BatchNormalization(xpayload, spayload, bpayload, mpayload, vpayload, cpayload, spatial, normalization_time_constant, blend_time_constant, epsilon, use_cudnn_engine);
+            });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "BatchNormalization", &[&xv, &sv, &bv, running_mean, running_variance, running_count])?;
         payload
-    }}
+    };
+    Ok(Function {payload})
+}
+
+/// Layer normalization: normalizes every sample to zero mean and unit variance over all static axes,
+/// then applies elementwise `scale` and `bias`.
+pub fn layer_normalization<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, scale: U, bias: V, epsilon: f64) -> Function {
+    try_layer_normalization(x, scale, bias, epsilon).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_layer_normalization<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, scale: U, bias: V, epsilon: f64) -> Result<Function, Error> {
+    let xv = x.into();
+    let epsilon = match xv.data_type() {
+        DataType::Double => Variable::try_constant_scalar_of::<f64>(epsilon)?,
+        _ => Variable::try_constant_scalar_of::<f32>(epsilon as f32)?,
+    };
+    let centered = try_minus(&xv, try_reduce_mean(&xv, &Axis::all_static())?)?;
+    let variance = try_reduce_mean(try_square(&centered)?, &Axis::all_static())?;
+    let normalized = try_element_divide(&centered, try_sqrt(try_plus(variance, epsilon)?)?)?;
+    try_plus(try_element_times(scale, normalized)?, bias)
+}
+
+/// Local response normalization across channels (the last static axis) as in AlexNet:
+/// `x / (bias + alpha * sum of squares of 2 * depth_radius + 1 neighbouring channels) ^ beta`.
+pub fn local_response_normalization<T: Into<Variable>>(x: T, depth_radius: usize, bias: f64, alpha: f64, beta: f64) -> Function {
+    try_local_response_normalization(x, depth_radius, bias, alpha, beta).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_local_response_normalization<T: Into<Variable>>(x: T, depth_radius: usize, bias: f64, alpha: f64, beta: f64) -> Result<Function, Error> {
+    let xv = x.into();
+    let xpayload = xv.payload;
+    let payload = unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([xpayload as "Variable", depth_radius as "size_t", bias as "double", alpha as "double", beta as "double", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&] { return LocalResponseNormalization(xpayload, depth_radius, bias, alpha, beta); });
+        });
+        check_error(error_p, "LocalResponseNormalization", &[&xv])?;
+        payload
+    };
+    Ok(Function {payload})
 }
 
 pub fn clip<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, min: U, max: V) -> Function {
+    try_clip(x, min, max).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_clip<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, min: U, max: V) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload = xv.payload;
     let minv = min.into();
@@ -1920,27 +1873,21 @@ pub fn clip<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>>(x: T, min:
     let maxv = max.into();
     let maxpayload = maxv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", minpayload as "Variable", maxpayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Clip(xpayload, minpayload, maxpayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Clip(xpayload, minpayload, maxpayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Clip", &[&xv, &minv, &maxv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn nce_loss<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>, W: Into<Variable>, X: Into<Variable>>(weights: T, biases: U, inputs: V, labels: W, noise_weights: X, num_samples: usize) -> Function {
+    try_nce_loss(weights, biases, inputs, labels, noise_weights, num_samples).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_nce_loss<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>, W: Into<Variable>, X: Into<Variable>>(weights: T, biases: U, inputs: V, labels: W, noise_weights: X, num_samples: usize) -> Result<Function, Error> {
     let wv = weights.into();
     let bv = biases.into();
     let iv = inputs.into();
@@ -1951,98 +1898,88 @@ pub fn nce_loss<T: Into<Variable>, U: Into<Variable>, V: Into<Variable>, W: Into
     let ivp = iv.payload;
     let lvp = lv.payload;
     let nvp = nv.payload;
-    Function { payload: unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([wvp as "Variable", bvp as "Variable", ivp as "Variable", lvp as "Variable", nvp as "Variable", num_samples as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return NCELoss(wvp, bvp, ivp, lvp, Constant(nvp), num_samples);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return NCELoss(wvp, bvp, ivp, lvp, Constant(nvp), num_samples); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "NCELoss", &[&wv, &bv, &iv, &lv, &nv])?;
         payload
-    }}
+    }})
 }
 
 pub fn broadcast_as<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_broadcast_as(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_broadcast_as<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.payload;
     let ypayload: VariableInner = yv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sequence::BroadcastAs(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sequence::BroadcastAs(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sequence::BroadcastAs", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn unpack<T: Into<Variable>>(x: T, padding_value: f32) -> Function {
+    try_unpack(x, padding_value).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_unpack<T: Into<Variable>>(x: T, padding_value: f32) -> Result<Function, Error> {
     let xv = x.into();
     let xpayload: VariableInner = xv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", padding_value as "float", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return Sequence::Unpack(xpayload, padding_value, true);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return Sequence::Unpack(xpayload, padding_value, true); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "Sequence::Unpack", &[&xv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
 }
 
 pub fn to_sequence_like<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Function {
+    try_to_sequence_like(x, y).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_to_sequence_like<T: Into<Variable>, U: Into<Variable>>(x: T, y: U) -> Result<Function, Error> {
     let xv = x.into();
     let yv = y.into();
     let xpayload: VariableInner = xv.payload;
     let ypayload: VariableInner = yv.payload;
     let payload = unsafe {
-        let mut error_p: *mut i8 = ptr::null_mut();
+        let mut error_p: *mut c_char = ptr::null_mut();
         let payload = cpp!([xpayload as "Variable", ypayload as "Variable", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
-            try {
-                return ToSequenceLike(xpayload, ypayload);
-            } catch (std::exception& e) {
-                auto what = e.what();
-                error_p = new char[strlen(what)+1];
-                strcpy(error_p, what);
-                return nullptr;
-            }
+            return cntk_rs_guard(error_p, [&] { return ToSequenceLike(xpayload, ypayload); });
         });
-        if !error_p.is_null() {
-             let msg = CStr::from_ptr(error_p).to_str().unwrap();
-             panic!("{}", msg);
-        }
+        check_error(error_p, "ToSequenceLike", &[&xv, &yv])?;
         payload
     };
-    Function {payload}
+    Ok(Function {payload})
+}
+
+pub fn cast<T: Into<Variable>>(x: T, output_type: DataType) -> Function {
+    try_cast(x, output_type).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_cast<T: Into<Variable>>(x: T, output_type: DataType) -> Result<Function, Error> {
+    let xv = x.into();
+    let xpayload: VariableInner = xv.payload;
+    let payload = unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([xpayload as "Variable", output_type as "DataType", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&] { return Cast(xpayload, output_type); });
+        });
+        check_error(error_p, "Cast", &[&xv])?;
+        payload
+    };
+    Ok(Function {payload})
 }
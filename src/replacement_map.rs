@@ -1,4 +1,5 @@
-use variable::Variable;
+use variable::{Variable, Placeholder};
+use std::borrow::Borrow;
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -12,7 +13,7 @@ cpp! {{
 
 type ReplacementMapInner = [u64; 1usize];
 
-/// Wrapper around unordered_map<Variable, Variable> to pass replacement for placeholders
+/// Wrapper around unordered_map<Variable, Variable> to pass replacement for placeholders (see `Function::replace_placeholders`)
 pub struct ReplacementMap {
     pub(super) payload: *mut ReplacementMapInner
 }
@@ -29,9 +30,10 @@ impl ReplacementMap {
         }
     }
 
-    /// Adds mapping to ReplacementMap. If mapping for given Variable exists, it will be overwritten.
-    pub fn add<T: Into<Variable>>(&mut self, variable: &Variable, replacement: T) {
-        let var_payload = variable.payload;
+    /// Adds mapping to ReplacementMap. If mapping for given Placeholder exists, it is kept.
+    pub fn add<P: Borrow<Placeholder>, T: Into<Variable>>(&mut self, placeholder: P, replacement: T) {
+        let placeholder: &Placeholder = placeholder.borrow();
+        let var_payload = placeholder.payload;
         let rv = replacement.into();
         let repl_payload = rv.payload;
         let mut payload = self.payload;
@@ -86,7 +88,7 @@ mod tests {
     #[test]
     fn test_add_and_get() {
         let mut map = ReplacementMap::new();
-        let var = Variable::input_variable(&Shape::scalar());
+        let var = Variable::placeholder(&Shape::scalar());
         let var2 = Variable::input_variable(&Shape::scalar());
         
         map.add(&var, &var2);
@@ -1,4 +1,8 @@
 use std::borrow::Borrow;
+use std::fmt;
+use std::ptr;
+use std::os::raw::c_char;
+use error::{Error, check_error};
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -16,6 +20,11 @@ pub struct Shape {
 }
 
 impl Shape {
+    /// Dimension which is inferred from inputs when function is composed (e.g. input dimension of parameter)
+    pub const INFERRED_DIMENSION: usize = !0;
+    /// Dimension which can have different size in every minibatch (e.g. spatial dimension of image input)
+    pub const FREE_DIMENSION: usize = !0 - 2;
+
     pub fn scalar() -> Shape {
         Shape {payload: unsafe {
             cpp!([] -> ShapeInner as "NDShape" {
@@ -36,15 +45,79 @@ impl Shape {
         }}
     }
 
-    pub fn total_size(&self) -> usize {
+    /// Shape of unknown rank (e.g. shape of placeholder which will be inferred)
+    pub fn unknown() -> Shape {
+        Shape {payload: unsafe {
+            cpp!([] -> ShapeInner as "NDShape" {
+                return NDShape::Unknown();
+            })
+        }}
+    }
+
+    pub fn is_unknown(&self) -> bool {
         let payload = self.payload;
         unsafe {
-            cpp!([payload as "NDShape"] -> usize as "size_t" {
-                return payload.TotalSize();
+            cpp!([payload as "NDShape"] -> bool as "bool" {
+                return payload.IsUnknown();
+            })
+        }
+    }
+
+    pub fn has_inferred_dimension(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "NDShape"] -> bool as "bool" {
+                return payload.HasInferredDimension();
             })
         }
     }
 
+    pub fn has_free_dimension(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "NDShape"] -> bool as "bool" {
+                return payload.HasFreeDimension();
+            })
+        }
+    }
+
+    /// Shape consisting of axes from `begin` (inclusive) to `end` (exclusive)
+    pub fn sub_shape(&self, begin: usize, end: usize) -> Shape {
+        self.try_sub_shape(begin, end).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_sub_shape(&self, begin: usize, end: usize) -> Result<Shape, Error> {
+        if begin > end || end > self.rank() {
+            return Err(Error::new(&format!("Invalid sub shape range {}..{} of shape with rank {}", begin, end, self.rank()), "NDShape::SubShape", &[]));
+        }
+        let payload = self.payload;
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([payload as "NDShape", begin as "size_t", end as "size_t", mut error_p as "char*"] -> ShapeInner as "NDShape" {
+                return cntk_rs_guard(error_p, [&] { return payload.SubShape(begin, end); });
+            });
+            check_error(error_p, "NDShape::SubShape", &[])?;
+            Ok(Shape {payload})
+        }
+    }
+
+    pub fn total_size(&self) -> usize {
+        self.try_total_size().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fails for shapes with unknown rank or inferred/free dimensions.
+    pub fn try_total_size(&self) -> Result<usize, Error> {
+        let payload = self.payload;
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let total_size = cpp!([payload as "NDShape", mut error_p as "char*"] -> usize as "size_t" {
+                return cntk_rs_guard(error_p, [&] { return payload.TotalSize(); });
+            });
+            check_error(error_p, "NDShape::TotalSize", &[])?;
+            Ok(total_size)
+        }
+    }
+
     pub fn rank(&self) -> usize {
         let payload = self.payload;
         unsafe {
@@ -100,4 +173,55 @@ impl Drop for Shape {
             })
         };
     }
-}
\ No newline at end of file
+}
+
+impl Clone for Shape {
+    fn clone(&self) -> Self {
+        let xpayload = self.payload;
+        Shape {payload: unsafe {
+            cpp!([xpayload as "NDShape"] -> ShapeInner as "NDShape" {
+                return xpayload;
+            })
+        }}
+    }
+}
+
+impl PartialEq for Shape {
+    fn eq(&self, other: &Shape) -> bool {
+        let payload = self.payload;
+        let opayload = other.payload;
+        unsafe {
+            cpp!([payload as "NDShape", opayload as "NDShape"] -> bool as "bool" {
+                return payload == opayload;
+            })
+        }
+    }
+}
+
+impl Eq for Shape {}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_unknown() {
+            return write!(f, "[???]");
+        }
+        let dims = self.to_vec().into_iter().map(|x| match x {
+            Shape::INFERRED_DIMENSION => "?".to_owned(),
+            Shape::FREE_DIMENSION => "*".to_owned(),
+            x => x.to_string(),
+        }).collect::<Vec<_>>();
+        write!(f, "[{}]", dims.join(" x "))
+    }
+}
+
+impl fmt::Debug for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Shape{}", self)
+    }
+}
+
+impl<'a> From<&'a [usize]> for Shape {
+    fn from(dims: &'a [usize]) -> Shape {
+        Shape::new(dims.to_vec())
+    }
+}
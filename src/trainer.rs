@@ -3,7 +3,8 @@ use function::Function;
 use data_map::DataMap;
 use device::DeviceDescriptor;
 use std::ptr;
-use std::ffi::CStr;
+use std::os::raw::c_char;
+use error::{Error, check_error};
 
 
 cpp! {{
@@ -24,49 +25,58 @@ pub struct Trainer {
 
 impl Trainer {
     pub fn new(model: &Function, loss: &Function, learner: &Learner) -> Trainer {
+        Trainer::try_new(model, loss, learner).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(model: &Function, loss: &Function, learner: &Learner) -> Result<Trainer, Error> {
         let modelpayload = model.payload;
         let losspayload = loss.payload;
         let learnerpayload = learner.payload;
-        Trainer { payload: unsafe {
-            cpp!([modelpayload as "FunctionPtr", losspayload as "FunctionPtr", learnerpayload as "LearnerPtr"] -> TrainerInner as "TrainerPtr"{
-                return CreateTrainer(modelpayload, losspayload, { learnerpayload });
-            })
-        }}
+        Ok(Trainer { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([modelpayload as "FunctionPtr", losspayload as "FunctionPtr", learnerpayload as "LearnerPtr", mut error_p as "char*"] -> TrainerInner as "TrainerPtr" {
+                return cntk_rs_guard(error_p, [&] { return CreateTrainer(modelpayload, losspayload, { learnerpayload }); });
+            });
+            check_error(error_p, "CreateTrainer", &[])?;
+            payload
+        }})
     }
 
     pub fn new_with_evalatuion(model: &Function, loss: &Function, evaluation: &Function, learner: &Learner) -> Trainer {
+        Trainer::try_new_with_evalatuion(model, loss, evaluation, learner).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new_with_evalatuion(model: &Function, loss: &Function, evaluation: &Function, learner: &Learner) -> Result<Trainer, Error> {
         let modelpayload = model.payload;
         let losspayload = loss.payload;
         let learnerpayload = learner.payload;
         let evaluationpayload = evaluation.payload;
-        Trainer { payload: unsafe {
-            cpp!([modelpayload as "FunctionPtr", losspayload as "FunctionPtr", evaluationpayload as "FunctionPtr", learnerpayload as "LearnerPtr"] -> TrainerInner as "TrainerPtr"{
-                return CreateTrainer(modelpayload, losspayload, evaluationpayload, { learnerpayload });
-            })
-        }}
+        Ok(Trainer { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([modelpayload as "FunctionPtr", losspayload as "FunctionPtr", evaluationpayload as "FunctionPtr", learnerpayload as "LearnerPtr", mut error_p as "char*"] -> TrainerInner as "TrainerPtr" {
+                return cntk_rs_guard(error_p, [&] { return CreateTrainer(modelpayload, losspayload, evaluationpayload, { learnerpayload }); });
+            });
+            check_error(error_p, "CreateTrainer", &[])?;
+            payload
+        }})
     }
 
     pub fn train_minibatch(&self, arguments: &DataMap, outputs_to_fetch: &mut DataMap, device: DeviceDescriptor) {
+        self.try_train_minibatch(arguments, outputs_to_fetch, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_train_minibatch(&self, arguments: &DataMap, outputs_to_fetch: &mut DataMap, device: DeviceDescriptor) -> Result<(), Error> {
         let payload = self.payload;
         let impayload = arguments.payload;
         let mut ompayload = outputs_to_fetch.payload;
         let dpayload = device.payload;
         unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+            let mut error_p: *mut c_char = ptr::null_mut();
             cpp!([payload as "TrainerPtr", impayload as "unordered_map<Variable, ValuePtr>*", mut ompayload as "unordered_map<Variable, ValuePtr>*", dpayload as "DeviceDescriptor", mut error_p as "char*"] {
-                try {
-                    payload->TrainMinibatch(*impayload, false, *ompayload, dpayload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                }
+                cntk_rs_guard(error_p, [&] { payload->TrainMinibatch(*impayload, false, *ompayload, dpayload); });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
-        };
+            check_error(error_p, "Trainer::TrainMinibatch", &[])
+        }
     }
 }
 
@@ -0,0 +1,457 @@
+use variable::{Variable, VariableInner};
+use function::{Function, FunctionInner};
+use value::{Value, ValueInner};
+use shape::Shape;
+use device::DeviceDescriptor;
+use error::{Error, check_error};
+use std::any::Any;
+use std::borrow::Borrow;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::str;
+use std::sync::{Arc, Once};
+use std::os::raw::{c_char, c_void};
+
+cpp! {{
+  #include <CNTKLibrary.h>
+  #include <cstdio>
+  #include <vector>
+  #include <stdexcept>
+
+  using namespace CNTK;
+  using namespace std;
+
+  // Callbacks into Rust, obj is boxed Arc<UserFunction> owned by CntkRsUserFunction.
+  // Errors (panics of Rust code) are reported through the last argument.
+  struct CntkRsUdfCallbacks {
+      void (*output_shapes)(void* obj, const vector<Variable>* inputs, vector<NDShape>* shapes, string* error);
+      void (*forward)(void* obj, const vector<ValuePtr>* inputs, vector<ValuePtr>* outputs, const DeviceDescriptor* device, string* error);
+      void (*backward)(void* obj, const vector<ValuePtr>* inputs, const vector<ValuePtr>* outputs,
+                       const vector<ValuePtr>* output_gradients, vector<ValuePtr>* input_gradients, string* error);
+      void (*state)(void* obj, string* state, string* error);
+      void* (*clone)(void* obj, string* error);
+      void (*drop)(void* obj, string* error);
+      void* (*deserialize)(void* deserializer, const string* state, string* error);
+  };
+
+  static CntkRsUdfCallbacks cntk_rs_udf_callbacks;
+
+  static void cntk_rs_udf_check(const string& error) {
+      if (!error.empty()) {
+          throw runtime_error(error);
+      }
+  }
+
+  class CntkRsBackPropState final : public BackPropState {
+  public:
+      CntkRsBackPropState(const FunctionPtr& function, const DeviceDescriptor& device, const vector<ValuePtr>& inputs, const vector<ValuePtr>& outputs)
+          : BackPropState(function, device), m_inputs(inputs), m_outputs(outputs) {}
+
+      vector<ValuePtr> m_inputs;
+      vector<ValuePtr> m_outputs;
+  };
+
+  class CntkRsUserFunction final : public Function {
+  public:
+      CntkRsUserFunction(const vector<Variable>& inputs, void* obj, const wstring& op_name, const wstring& name)
+          : Function(inputs, name), m_obj(obj), m_op_name(op_name) {}
+
+      ~CntkRsUserFunction() {
+          // Destructor must not throw, panic of Drop is only reported
+          string error;
+          cntk_rs_udf_callbacks.drop(m_obj, &error);
+          if (!error.empty()) {
+              fprintf(stderr, "%s\n", error.c_str());
+          }
+      }
+
+      BackPropStatePtr Forward(const vector<ValuePtr>& inputValues, unordered_map<Variable, ValuePtr>& outputs,
+                               const DeviceDescriptor& computeDevice, const unordered_set<Variable>& outputsToRetainBackwardStateFor) override {
+          vector<ValuePtr> outputValues;
+          string error;
+          cntk_rs_udf_callbacks.forward(m_obj, &inputValues, &outputValues, &computeDevice, &error);
+          cntk_rs_udf_check(error);
+          auto outputVariables = Outputs();
+          if (outputValues.size() != outputVariables.size()) {
+              throw runtime_error("UserFunction::forward returned wrong number of outputs");
+          }
+          for (size_t i = 0; i < outputVariables.size(); i++) {
+              auto it = outputs.find(outputVariables[i]);
+              if (it == outputs.end()) {
+                  continue;
+              }
+              if (it->second) {
+                  it->second->CopyFrom(*outputValues[i]);
+              } else {
+                  it->second = outputValues[i];
+              }
+          }
+          if (outputsToRetainBackwardStateFor.empty()) {
+              return nullptr;
+          }
+          // CNTK may reuse memory of input values before backward pass
+          vector<ValuePtr> savedInputs;
+          for (auto& value : inputValues) {
+              savedInputs.push_back(value->DeepClone());
+          }
+          return MakeSharedObject<CntkRsBackPropState>(shared_from_this(), computeDevice, savedInputs, outputValues);
+      }
+
+      void Backward(const BackPropStatePtr& state, const unordered_map<Variable, ValuePtr>& rootGradientValues,
+                    unordered_map<Variable, ValuePtr>& backPropagatedGradientValuesForInputs) override {
+          auto rsState = dynamic_pointer_cast<CntkRsBackPropState>(state);
+          vector<ValuePtr> outputGradients;
+          for (auto& output : Outputs()) {
+              auto it = rootGradientValues.find(output);
+              outputGradients.push_back(it != rootGradientValues.end() ? it->second : nullptr);
+          }
+          vector<ValuePtr> inputGradients;
+          string error;
+          cntk_rs_udf_callbacks.backward(m_obj, &rsState->m_inputs, &rsState->m_outputs, &outputGradients, &inputGradients, &error);
+          cntk_rs_udf_check(error);
+          auto inputVariables = Inputs();
+          for (size_t i = 0; i < inputVariables.size() && i < inputGradients.size(); i++) {
+              auto it = backPropagatedGradientValuesForInputs.find(inputVariables[i]);
+              if (it == backPropagatedGradientValuesForInputs.end() || !inputGradients[i]) {
+                  continue;
+              }
+              if (it->second) {
+                  it->second->CopyFrom(*inputGradients[i]);
+              } else {
+                  it->second = inputGradients[i];
+              }
+          }
+      }
+
+      const wstring& OpName() const override {
+          return m_op_name;
+      }
+
+      size_t CurrentVersion() const override {
+          return 1;
+      }
+
+      Dictionary Serialize() const override {
+          string state;
+          string error;
+          cntk_rs_udf_callbacks.state(m_obj, &state, &error);
+          cntk_rs_udf_check(error);
+          Dictionary dict;
+          dict[L"state"] = wstring(state.begin(), state.end());
+          return dict;
+      }
+
+      FunctionPtr Clone(const vector<Variable>& clonedInputs) override {
+          string error;
+          void* obj = cntk_rs_udf_callbacks.clone(m_obj, &error);
+          cntk_rs_udf_check(error);
+          return MakeSharedObject<CntkRsUserFunction>(clonedInputs, obj, m_op_name, Name());
+      }
+
+      void InferOutputs(vector<Variable>& outputs) override {
+          auto inputs = Inputs();
+          if (inputs.empty()) {
+              InvalidArgument("UserFunction '%S' must have at least one input", m_op_name.c_str());
+          }
+          vector<NDShape> shapes;
+          string error;
+          cntk_rs_udf_callbacks.output_shapes(m_obj, &inputs, &shapes, &error);
+          cntk_rs_udf_check(error);
+          vector<Axis> dynamicAxes;
+          for (auto& input : inputs) {
+              if (!input.DynamicAxes().empty()) {
+                  dynamicAxes = input.DynamicAxes();
+                  break;
+              }
+          }
+          for (auto& shape : shapes) {
+              outputs.push_back(OutputVariable(shape, inputs[0].GetDataType(), dynamicAxes));
+          }
+      }
+
+  private:
+      void* m_obj;
+      wstring m_op_name;
+  };
+}}
+
+/// Operation implemented in Rust, which can be used in graph like any other function (see `user_function`).
+///
+/// Inputs and outputs have the same order as in `Function::inputs`/`Function::outputs`. Panics of these methods
+/// are turned into errors of the CNTK call which invoked them (e.g. `Function::try_evaluate`).
+pub trait UserFunction: Send + Sync + 'static {
+    /// Name of the operation, also used to find deserializer when loading saved model.
+    fn op_name(&self) -> String;
+
+    /// Shapes of outputs for given inputs. Outputs have data type of the first input and dynamic axes of
+    /// the first input having any.
+    fn output_shapes(&self, inputs: &[Variable]) -> Vec<Shape>;
+
+    fn forward(&self, inputs: &[Value], device: DeviceDescriptor) -> Vec<Value>;
+
+    /// Gradients with respect to inputs (None for inputs without gradient) given values from forward pass and
+    /// gradients of outputs (None for outputs not taking part in backpropagation).
+    fn backward(&self, inputs: &[Value], outputs: &[Value], output_gradients: &[Option<Value>]) -> Vec<Option<Value>>;
+
+    /// State saved with the model and passed to deserializer (only ASCII strings are supported).
+    fn state(&self) -> String {
+        String::new()
+    }
+}
+
+/// Creates deserialized UserFunction from its state, see `register_user_function_deserializer`.
+pub type UserFunctionDeserializer = fn(&str) -> Box<dyn UserFunction>;
+
+type UserFunctionObject = Arc<dyn UserFunction>;
+
+/// Creates function from Rust implementation of the operation.
+pub fn user_function<U: UserFunction, I: Borrow<Variable>>(function: U, inputs: &[I], name: &str) -> Function {
+    try_user_function(function, inputs, name).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_user_function<U: UserFunction, I: Borrow<Variable>>(function: U, inputs: &[I], name: &str) -> Result<Function, Error> {
+    register_callbacks();
+    let op_name = function.op_name();
+    let op_name_ptr = op_name.as_ptr();
+    let op_name_len = op_name.len();
+    let name_ptr = name.as_ptr();
+    let name_len = name.len();
+    let data: Vec<Variable> = inputs.iter().map(|x| x.borrow().clone()).collect();
+    let data_ptr = data.as_ptr();
+    let data_size = data.len();
+    let object: UserFunctionObject = Arc::new(function);
+    let obj = Box::into_raw(Box::new(object)) as *mut c_void;
+    Ok(Function { payload: unsafe {
+        let mut error_p: *mut c_char = ptr::null_mut();
+        let payload = cpp!([data_ptr as "Variable*", data_size as "size_t", obj as "void*", op_name_ptr as "char*", op_name_len as "size_t",
+                            name_ptr as "char*", name_len as "size_t", mut error_p as "char*"] -> FunctionInner as "FunctionPtr" {
+            return cntk_rs_guard(error_p, [&]() -> FunctionPtr {
+                string op_name(op_name_ptr, op_name_ptr + op_name_len);
+                wstring wop_name;
+                wop_name.assign(op_name.begin(), op_name.end());
+                string name(name_ptr, name_ptr + name_len);
+                wstring wname;
+                wname.assign(name.begin(), name.end());
+                return AsComposite(MakeSharedObject<CntkRsUserFunction>(vector<Variable>(data_ptr, data_ptr + data_size), obj, wop_name, wname));
+            });
+        });
+        check_error(error_p, "UserFunction", &data.iter().collect::<Vec<_>>())?;
+        payload
+    }})
+}
+
+/// Registers deserializer used by `Function::load` for user functions with given `op_name`.
+pub fn register_user_function_deserializer(op_name: &str, deserializer: UserFunctionDeserializer) {
+    try_register_user_function_deserializer(op_name, deserializer).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fails if deserializer for `op_name` is already registered.
+pub fn try_register_user_function_deserializer(op_name: &str, deserializer: UserFunctionDeserializer) -> Result<(), Error> {
+    register_callbacks();
+    let op_name_ptr = op_name.as_ptr();
+    let op_name_len = op_name.len();
+    let deserializer = deserializer as *const c_void;
+    let mut error_p: *mut c_char = ptr::null_mut();
+    unsafe {
+        cpp!([op_name_ptr as "char*", op_name_len as "size_t", deserializer as "void*", mut error_p as "char*"] {
+            cntk_rs_guard(error_p, [&] {
+                string op_name(op_name_ptr, op_name_ptr + op_name_len);
+                wstring wop_name;
+                wop_name.assign(op_name.begin(), op_name.end());
+                Function::RegisterUDFDeserializeCallback(wop_name, [deserializer, wop_name](const vector<Variable>& inputs, const wstring& name, const Dictionary& dict) -> FunctionPtr {
+                    auto wstate = dict[L"state"].Value<wstring>();
+                    string state(wstate.begin(), wstate.end());
+                    string error;
+                    void* obj = cntk_rs_udf_callbacks.deserialize(deserializer, &state, &error);
+                    cntk_rs_udf_check(error);
+                    return MakeSharedObject<CntkRsUserFunction>(inputs, obj, wop_name, name);
+                });
+            });
+        });
+        check_error(error_p, "Function::RegisterUDFDeserializeCallback", &[])
+    }
+}
+
+static REGISTER_CALLBACKS: Once = Once::new();
+
+fn register_callbacks() {
+    REGISTER_CALLBACKS.call_once(|| {
+        let output_shapes = udf_output_shapes as *const c_void;
+        let forward = udf_forward as *const c_void;
+        let backward = udf_backward as *const c_void;
+        let state = udf_state as *const c_void;
+        let clone = udf_clone as *const c_void;
+        let drop = udf_drop as *const c_void;
+        let deserialize = udf_deserialize as *const c_void;
+        unsafe {
+            cpp!([output_shapes as "void*", forward as "void*", backward as "void*", state as "void*", clone as "void*", drop as "void*", deserialize as "void*"] {
+                cntk_rs_udf_callbacks.output_shapes = (decltype(cntk_rs_udf_callbacks.output_shapes))output_shapes;
+                cntk_rs_udf_callbacks.forward = (decltype(cntk_rs_udf_callbacks.forward))forward;
+                cntk_rs_udf_callbacks.backward = (decltype(cntk_rs_udf_callbacks.backward))backward;
+                cntk_rs_udf_callbacks.state = (decltype(cntk_rs_udf_callbacks.state))state;
+                cntk_rs_udf_callbacks.clone = (decltype(cntk_rs_udf_callbacks.clone))clone;
+                cntk_rs_udf_callbacks.drop = (decltype(cntk_rs_udf_callbacks.drop))drop;
+                cntk_rs_udf_callbacks.deserialize = (decltype(cntk_rs_udf_callbacks.deserialize))deserialize;
+            })
+        }
+    });
+}
+
+unsafe fn udf_object<'a>(obj: *mut c_void) -> &'a UserFunctionObject {
+    &*(obj as *const UserFunctionObject)
+}
+
+/// Runs `f` and stores message of its panic into `error` (`string*`), so that no panic unwinds into C++.
+unsafe fn udf_guard<F: FnOnce()>(error: *mut c_void, f: F) {
+    if let Err(cause) = panic::catch_unwind(AssertUnwindSafe(f)) {
+        let message = panic_message(&*cause);
+        let message_ptr = message.as_ptr();
+        let message_len = message.len();
+        cpp!([error as "string*", message_ptr as "char*", message_len as "size_t"] {
+            *error = string(message_ptr, message_ptr + message_len);
+        });
+    }
+}
+
+fn panic_message(cause: &(dyn Any + Send)) -> String {
+    if let Some(message) = cause.downcast_ref::<String>() {
+        format!("UserFunction panicked: {}", message)
+    } else if let Some(message) = cause.downcast_ref::<&str>() {
+        format!("UserFunction panicked: {}", message)
+    } else {
+        "UserFunction panicked".to_string()
+    }
+}
+
+/// Copies `vector<ValuePtr>` (with null pointers becoming None).
+unsafe fn values_from_vector(values: *const c_void) -> Vec<Option<Value>> {
+    let size = cpp!([values as "const vector<ValuePtr>*"] -> usize as "size_t" {
+        return values->size();
+    });
+    (0..size).map(|i| {
+        let is_null = cpp!([values as "const vector<ValuePtr>*", i as "size_t"] -> bool as "bool" {
+            return (*values)[i] == nullptr;
+        });
+        if is_null {
+            None
+        } else {
+            Some(Value { payload: cpp!([values as "const vector<ValuePtr>*", i as "size_t"] -> ValueInner as "ValuePtr" {
+                return (*values)[i];
+            })})
+        }
+    }).collect()
+}
+
+unsafe fn push_value(values: *mut c_void, value: Option<Value>) {
+    match value {
+        Some(value) => {
+            let payload = value.payload;
+            cpp!([values as "vector<ValuePtr>*", payload as "ValuePtr"] {
+                values->push_back(payload);
+            })
+        },
+        None => cpp!([values as "vector<ValuePtr>*"] {
+            values->push_back(nullptr);
+        }),
+    }
+}
+
+extern "C" fn udf_output_shapes(obj: *mut c_void, inputs: *const c_void, shapes: *mut c_void, error: *mut c_void) {
+    unsafe {
+        udf_guard(error, || {
+            let num_inputs = cpp!([inputs as "const vector<Variable>*"] -> usize as "size_t" {
+                return inputs->size();
+            });
+            let variables = (0..num_inputs).map(|i| Variable { payload: cpp!([inputs as "const vector<Variable>*", i as "size_t"] -> VariableInner as "Variable" {
+                return (*inputs)[i];
+            })}).collect::<Vec<_>>();
+            for shape in udf_object(obj).output_shapes(&variables) {
+                let spayload = shape.payload;
+                cpp!([shapes as "vector<NDShape>*", spayload as "NDShape"] {
+                    shapes->push_back(spayload);
+                })
+            }
+        })
+    }
+}
+
+extern "C" fn udf_forward(obj: *mut c_void, inputs: *const c_void, outputs: *mut c_void, device: *const c_void, error: *mut c_void) {
+    unsafe {
+        udf_guard(error, || {
+            let inputs = values_from_vector(inputs).into_iter().map(|x| x.expect("Missing input value")).collect::<Vec<_>>();
+            let device = DeviceDescriptor { payload: cpp!([device as "const DeviceDescriptor*"] -> [u32; 2] as "DeviceDescriptor" {
+                return *device;
+            })};
+            for value in udf_object(obj).forward(&inputs, device) {
+                push_value(outputs, Some(value));
+            }
+        })
+    }
+}
+
+extern "C" fn udf_backward(obj: *mut c_void, inputs: *const c_void, outputs: *const c_void, output_gradients: *const c_void,
+                           input_gradients: *mut c_void, error: *mut c_void) {
+    unsafe {
+        udf_guard(error, || {
+            let inputs = values_from_vector(inputs).into_iter().map(|x| x.expect("Missing input value")).collect::<Vec<_>>();
+            let outputs = values_from_vector(outputs).into_iter().map(|x| x.expect("Missing output value")).collect::<Vec<_>>();
+            let output_gradients = values_from_vector(output_gradients);
+            for gradient in udf_object(obj).backward(&inputs, &outputs, &output_gradients) {
+                push_value(input_gradients, gradient);
+            }
+        })
+    }
+}
+
+extern "C" fn udf_state(obj: *mut c_void, state: *mut c_void, error: *mut c_void) {
+    unsafe {
+        udf_guard(error, || {
+            let value = udf_object(obj).state();
+            let value_ptr = value.as_ptr();
+            let value_len = value.len();
+            cpp!([state as "string*", value_ptr as "char*", value_len as "size_t"] {
+                *state = string(value_ptr, value_ptr + value_len);
+            })
+        })
+    }
+}
+
+extern "C" fn udf_clone(obj: *mut c_void, error: *mut c_void) -> *mut c_void {
+    let mut cloned = ptr::null_mut();
+    unsafe {
+        udf_guard(error, || {
+            let object = udf_object(obj).clone();
+            cloned = Box::into_raw(Box::new(object)) as *mut c_void;
+        })
+    }
+    cloned
+}
+
+extern "C" fn udf_drop(obj: *mut c_void, error: *mut c_void) {
+    unsafe {
+        udf_guard(error, || {
+            drop(Box::from_raw(obj as *mut UserFunctionObject));
+        })
+    }
+}
+
+extern "C" fn udf_deserialize(deserializer: *mut c_void, state: *const c_void, error: *mut c_void) -> *mut c_void {
+    let mut obj = ptr::null_mut();
+    unsafe {
+        udf_guard(error, || {
+            let deserializer: UserFunctionDeserializer = mem::transmute(deserializer);
+            let state_ptr = cpp!([state as "const string*"] -> *const u8 as "const char*" {
+                return state->data();
+            });
+            let state_len = cpp!([state as "const string*"] -> usize as "size_t" {
+                return state->size();
+            });
+            let state = str::from_utf8(slice::from_raw_parts(state_ptr, state_len)).unwrap();
+            let object: UserFunctionObject = Arc::from(deserializer(state));
+            obj = Box::into_raw(Box::new(object)) as *mut c_void;
+        })
+    }
+    obj
+}
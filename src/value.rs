@@ -1,9 +1,13 @@
 use shape::{Shape, ShapeInner};
+use nd_array_view::{NDArrayView, NDArrayViewInner, NDArrayLayout};
 use device::DeviceDescriptor;
+use data_type::{DataType, CntkElement};
 use std::ptr;
-use std::borrow::Borrow;
-use std::ffi::CStr;
-use ndarray::{Array, Dimension, ArrayD, ArrayBase, Data};
+use std::os::raw::{c_char, c_void};
+use std::borrow::{Borrow, Cow};
+use std::marker::PhantomData;
+use error::{Error, check_error};
+use ndarray::{Array, Dimension, ArrayD, ArrayBase, Data, DataMut};
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -12,6 +16,54 @@ cpp! {{
 
   using namespace CNTK;
   using namespace std;
+
+  template <typename T>
+  static vector<T> cntk_rs_vector(const void* data_ptr, size_t data_size) {
+      const T* data = static_cast<const T*>(data_ptr);
+      return vector<T>(data, data + data_size);
+  }
+
+  template <typename T>
+  static vector<vector<T>> cntk_rs_vectors(const void* const* data_ptr, const size_t* sizes_ptr, size_t n_batches) {
+      vector<vector<T>> data;
+      for (size_t i = 0; i < n_batches; i++) {
+          data.push_back(cntk_rs_vector<T>(data_ptr[i], sizes_ptr[i]));
+      }
+      return data;
+  }
+
+  // Copies CSC buffers of sparse view (only when output pointers are set) and returns number of non-zero values.
+  template <typename T>
+  static size_t cntk_rs_copy_csc(NDArrayViewPtr view, size_t num_columns, int* col_starts, int* row_indices, void* values) {
+      auto buffers = view->SparseCSCDataBuffers<T>();
+      size_t nnz = get<3>(buffers);
+      if (values != nullptr) {
+          copy(get<0>(buffers), get<0>(buffers) + nnz, static_cast<T*>(values));
+          copy(get<1>(buffers), get<1>(buffers) + num_columns + 1, col_starts);
+          copy(get<2>(buffers), get<2>(buffers) + nnz, row_indices);
+      }
+      return nnz;
+  }
+
+  // Value over borrowed Rust data (see BorrowedValue). Copies given out to Rust get their own data,
+  // since they could outlive the borrow.
+  class CntkRsBorrowedValue : public Value {
+  public:
+      CntkRsBorrowedValue(const NDArrayViewPtr& data) : Value(data) {}
+      bool erased = false;
+  };
+
+  // Returns value which can be handed out to Rust: borrowed values are deep copied, erased ones become null.
+  static ValuePtr cntk_rs_owned_value(const ValuePtr& value) {
+      auto borrowed = dynamic_pointer_cast<CntkRsBorrowedValue>(value);
+      if (!borrowed) {
+          return value;
+      }
+      if (borrowed->erased) {
+          return nullptr;
+      }
+      return borrowed->DeepClone();
+  }
 }}
 
 pub(super) type ValueInner = [u64; 2usize];
@@ -21,215 +73,462 @@ pub struct Value {
     pub(super) payload: ValueInner
 }
 
+/// State of one step of a sequence in Value (mirrors CNTK MaskKind enum)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaskKind {
+    /// Padding
+    Invalid = 0,
+    /// Step continuing the sequence
+    Valid = 1,
+    /// First step of a sequence which is not continuation of sequence from previous minibatch
+    SequenceBegin = 2,
+}
+
+/// Value referencing borrowed Rust data without copying it (see `Value::borrow_slice`), bound into
+/// DataMap with `DataMap::add_borrowed`. When dropped, the underlying CNTK Value is erased, so copies
+/// of it (e.g. in DataMap) can no longer reach the borrowed data. Values read back from DataMap are
+/// copies of the borrowed data.
+pub struct BorrowedValue<'a> {
+    value: Value,
+    _data: PhantomData<&'a [u8]>
+}
+
+/// Sparse data in compressed sparse column (CSC) format.
+/// Rows are the first (fastest changing) axis of CNTK shape, every other position is a column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseCsc<T = f32> {
+    /// Index into `row_indices`/`values` where each column starts (number of columns + 1 entries)
+    pub col_starts: Vec<i32>,
+    /// Row of each non-zero value
+    pub row_indices: Vec<i32>,
+    /// Non-zero values
+    pub values: Vec<T>,
+}
+
 impl Value {
-    fn batch(data_ptr: *const f32, data_size: usize, shape: &Shape, device: DeviceDescriptor) -> Value {
+    fn batch(data_ptr: *const c_void, data_size: usize, dtype: DataType, shape: &Shape, device: DeviceDescriptor) -> Result<Value, Error> {
         let shape_payload = shape.payload;
         let device_payload = device.payload;
         let payload = unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
-            let payload = cpp!([shape_payload as "NDShape", data_ptr as "float*", data_size as "size_t", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
-                try {
-                    vector<float> data(data_ptr, data_ptr + data_size);
-                    return Value::CreateBatch(shape_payload, data, device_payload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", data_ptr as "const void*", data_size as "size_t", dtype as "DataType", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    if (dtype == DataType::Double) {
+                        return Value::CreateBatch(shape_payload, cntk_rs_vector<double>(data_ptr, data_size), device_payload);
+                    }
+                    return Value::CreateBatch(shape_payload, cntk_rs_vector<float>(data_ptr, data_size), device_payload);
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Value::CreateBatch", &[])?;
             payload
         };
-        Value { payload }
+        Ok(Value { payload })
     }
 
     pub fn batch_from_vec(shape: &Shape, data: &[f32], device: DeviceDescriptor) -> Value {
-        let data_ptr = data.as_ptr();
+        Value::batch_from_vec_of::<f32>(shape, data, device)
+    }
+
+    pub fn batch_from_vec_of<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Value {
+        Value::try_batch_from_vec_of(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_from_vec(shape: &Shape, data: &[f32], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_from_vec_of::<f32>(shape, data, device)
+    }
+
+    pub fn try_batch_from_vec_of<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Result<Value, Error> {
+        let data_ptr = data.as_ptr() as *const c_void;
         let data_size = data.len();
-        Value::batch(data_ptr, data_size, shape, device)
+        Value::batch(data_ptr, data_size, T::data_type(), shape, device)
     }
 
     pub fn batch_from_ndarray<D: Dimension, S: Data<Elem=f32>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Value {
-        assert!(data.is_standard_layout(), "CNTK only supports NDArrays with standard layout");
-        let expected_shape = shape.to_vec_reversed();
-        let data_shape = data.shape();
-        assert!(data_shape.len() >= expected_shape.len());
-        assert!(expected_shape == &data_shape[data_shape.len() - expected_shape.len()..]);
-        let data_ptr = data.as_ptr();
+        Value::batch_from_ndarray_of::<f32, D, S>(shape, data, device)
+    }
+
+    pub fn batch_from_ndarray_of<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Value {
+        Value::try_batch_from_ndarray_of(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_from_ndarray<D: Dimension, S: Data<Elem=f32>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_from_ndarray_of::<f32, D, S>(shape, data, device)
+    }
+
+    pub fn try_batch_from_ndarray_of<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Value, Error> {
+        check_ndarray(shape, data, false, "Value::batch_from_ndarray")?;
+        let data = ndarray_data(data);
+        let data_ptr = data.as_ptr() as *const c_void;
         let data_size = data.len();
-        Value::batch(data_ptr, data_size, shape, device)
+        Value::batch(data_ptr, data_size, T::data_type(), shape, device)
     }
 
-    fn sequence(data_ptr: *const f32, data_size: usize, shape: &Shape, device: DeviceDescriptor) -> Value {
+    fn sequence(data_ptr: *const c_void, data_size: usize, dtype: DataType, shape: &Shape, device: DeviceDescriptor) -> Result<Value, Error> {
         let shape_payload = shape.payload;
         let device_payload = device.payload;
         let payload = unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
-            let payload = cpp!([shape_payload as "NDShape", data_ptr as "float*", data_size as "size_t", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
-                try {
-                    vector<float> data(data_ptr, data_ptr + data_size);
-                    return Value::CreateSequence(shape_payload, data, device_payload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", data_ptr as "const void*", data_size as "size_t", dtype as "DataType", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    if (dtype == DataType::Double) {
+                        return Value::CreateSequence(shape_payload, cntk_rs_vector<double>(data_ptr, data_size), device_payload);
+                    }
+                    return Value::CreateSequence(shape_payload, cntk_rs_vector<float>(data_ptr, data_size), device_payload);
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Value::CreateSequence", &[])?;
             payload
         };
-        Value { payload }
+        Ok(Value { payload })
     }
 
     pub fn sequence_from_vec(shape: &Shape, data: &[f32], device: DeviceDescriptor) -> Value {
-        let data_ptr = data.as_ptr();
+        Value::sequence_from_vec_of::<f32>(shape, data, device)
+    }
+
+    pub fn sequence_from_vec_of<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Value {
+        Value::try_sequence_from_vec_of(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_sequence_from_vec(shape: &Shape, data: &[f32], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_sequence_from_vec_of::<f32>(shape, data, device)
+    }
+
+    pub fn try_sequence_from_vec_of<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Result<Value, Error> {
+        let data_ptr = data.as_ptr() as *const c_void;
         let data_size = data.len();
-        Value::sequence(data_ptr, data_size, shape, device)
+        Value::sequence(data_ptr, data_size, T::data_type(), shape, device)
     }
 
     pub fn sequence_from_ndarray<D: Dimension, S: Data<Elem=f32>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Value {
-        assert!(data.is_standard_layout(), "CNTK only supports NDArrays with standard layout");
-        let expected_shape = shape.to_vec_reversed();
-        let data_shape = data.shape();
-        assert!(data_shape.len() >= expected_shape.len());
-        assert!(expected_shape == &data_shape[data_shape.len() - expected_shape.len()..]);
-        let data_ptr = data.as_ptr();
+        Value::sequence_from_ndarray_of::<f32, D, S>(shape, data, device)
+    }
+
+    pub fn sequence_from_ndarray_of<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Value {
+        Value::try_sequence_from_ndarray_of(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_sequence_from_ndarray<D: Dimension, S: Data<Elem=f32>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_sequence_from_ndarray_of::<f32, D, S>(shape, data, device)
+    }
+
+    pub fn try_sequence_from_ndarray_of<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Value, Error> {
+        check_ndarray(shape, data, false, "Value::sequence_from_ndarray")?;
+        let data = ndarray_data(data);
+        let data_ptr = data.as_ptr() as *const c_void;
         let data_size = data.len();
-        Value::sequence(data_ptr, data_size, shape, device)
+        Value::sequence(data_ptr, data_size, T::data_type(), shape, device)
     }
 
-    fn from_ptr(data_ptr: *const f32, data_size: usize, shape: &Shape, device: DeviceDescriptor) -> Value {
+    fn from_ptr(data_ptr: *const c_void, data_size: usize, dtype: DataType, shape: &Shape, device: DeviceDescriptor) -> Result<Value, Error> {
         let shape_payload = shape.payload;
         let device_payload = device.payload;
         let payload = unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
-            let payload = cpp!([shape_payload as "NDShape", data_ptr as "float*", data_size as "size_t", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
-                try {
-                    return MakeSharedObject<Value>(MakeSharedObject<NDArrayView>(shape_payload, data_ptr, data_size, device_payload, true)->DeepClone());
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", data_ptr as "const void*", data_size as "size_t", dtype as "DataType", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    auto view = MakeSharedObject<NDArrayView>(dtype, shape_payload, const_cast<void*>(data_ptr), data_size * DataTypeSize(dtype), device_payload, true);
+                    return MakeSharedObject<Value>(view->DeepClone());
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Value::Value", &[])?;
             payload
         };
-        Value { payload }
+        Ok(Value { payload })
     }
 
     pub fn from_vec(shape: &Shape, data: &[f32], device: DeviceDescriptor) -> Value {
-        let data_ptr = data.as_ptr();
+        Value::from_vec_of::<f32>(shape, data, device)
+    }
+
+    pub fn from_vec_of<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Value {
+        Value::try_from_vec_of(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_vec(shape: &Shape, data: &[f32], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_from_vec_of::<f32>(shape, data, device)
+    }
+
+    pub fn try_from_vec_of<T: CntkElement>(shape: &Shape, data: &[T], device: DeviceDescriptor) -> Result<Value, Error> {
+        let data_ptr = data.as_ptr() as *const c_void;
         let data_size = data.len();
 
-        Value::from_ptr(data_ptr, data_size, shape, device)
+        Value::from_ptr(data_ptr, data_size, T::data_type(), shape, device)
+    }
+
+    /// Creates Value with copy of the data, having the same shape, mask and device as `like`
+    /// (e.g. to return result of elementwise operation over sequences from `UserFunction`).
+    pub fn from_vec_like(like: &Value, data: &[f32]) -> Value {
+        Value::from_vec_like_of::<f32>(like, data)
+    }
+
+    pub fn from_vec_like_of<T: CntkElement>(like: &Value, data: &[T]) -> Value {
+        Value::try_from_vec_like_of(like, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_vec_like(like: &Value, data: &[f32]) -> Result<Value, Error> {
+        Value::try_from_vec_like_of::<f32>(like, data)
+    }
+
+    pub fn try_from_vec_like_of<T: CntkElement>(like: &Value, data: &[T]) -> Result<Value, Error> {
+        if like.shape().try_total_size()? != data.len() {
+            return Err(Error::new("Data size does not match size of the Value", "Value::from_vec_like", &[]));
+        }
+        let lpayload = like.payload;
+        let data_ptr = data.as_ptr() as *const c_void;
+        let data_size = data.len();
+        let dtype = T::data_type();
+        let payload = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([lpayload as "ValuePtr", data_ptr as "const void*", data_size as "size_t", dtype as "DataType", mut error_p as "char*"] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    auto view = MakeSharedObject<NDArrayView>(dtype, lpayload->Shape(), const_cast<void*>(data_ptr), data_size * DataTypeSize(dtype), DeviceDescriptor::CPUDevice(), true);
+                    auto mask = lpayload->Mask();
+                    return MakeSharedObject<Value>(view->DeepClone(lpayload->Device(), false), mask ? mask->DeepClone() : nullptr);
+                });
+            });
+            check_error(error_p, "Value::Value", &[])?;
+            payload
+        };
+        Ok(Value { payload })
     }
 
     pub fn from_ndarray<D: Dimension, S: Data<Elem=f32>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Value {
-        assert!(data.is_standard_layout(), "CNTK only supports NDArrays with standard layout");
-        let expected_shape = shape.to_vec_reversed();
-        let data_shape = data.shape();
-        assert!(data_shape == &expected_shape[..]);
-        let data_ptr = data.as_ptr();
+        Value::from_ndarray_of::<f32, D, S>(shape, data, device)
+    }
+
+    pub fn from_ndarray_of<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Value {
+        Value::try_from_ndarray_of(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_ndarray<D: Dimension, S: Data<Elem=f32>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_from_ndarray_of::<f32, D, S>(shape, data, device)
+    }
+
+    pub fn try_from_ndarray_of<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Value, Error> {
+        check_ndarray(shape, data, true, "Value::from_ndarray")?;
+        let data = ndarray_data(data);
+        let data_ptr = data.as_ptr() as *const c_void;
         let data_size = data.len();
-        Value::from_ptr(data_ptr, data_size, shape, device)
+        Value::from_ptr(data_ptr, data_size, T::data_type(), shape, device)
+    }
+
+    /// Creates read-only Value on CPU directly over the borrowed data (without copying).
+    /// Shape contains sample shape followed by sequence and batch axes (same as in `from_vec`).
+    pub fn borrow_slice<'a, T: CntkElement>(shape: &Shape, data: &'a [T]) -> BorrowedValue<'a> {
+        Value::try_borrow_slice(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_borrow_slice<'a, T: CntkElement>(shape: &Shape, data: &'a [T]) -> Result<BorrowedValue<'a>, Error> {
+        Value::borrow_ptr(shape, data.as_ptr() as *mut c_void, data.len(), T::data_type(), true, "Value::borrow_slice")
+    }
+
+    /// Creates writable Value on CPU directly over the borrowed buffer (without copying).
+    /// Adding it to output DataMap makes `Function::evaluate` write results directly into the buffer,
+    /// which is accessible again once the BorrowedValue is dropped.
+    pub fn borrow_slice_mut<'a, T: CntkElement>(shape: &Shape, data: &'a mut [T]) -> BorrowedValue<'a> {
+        Value::try_borrow_slice_mut(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_borrow_slice_mut<'a, T: CntkElement>(shape: &Shape, data: &'a mut [T]) -> Result<BorrowedValue<'a>, Error> {
+        Value::borrow_ptr(shape, data.as_mut_ptr() as *mut c_void, data.len(), T::data_type(), false, "Value::borrow_slice_mut")
+    }
+
+    fn borrow_ptr<'a>(shape: &Shape, data_ptr: *mut c_void, data_size: usize, dtype: DataType, read_only: bool, call: &'static str) -> Result<BorrowedValue<'a>, Error> {
+        let total_size = shape.try_total_size()?;
+        if total_size != data_size {
+            return Err(Error::new(&format!("Data size {} does not match shape size {}", data_size, total_size), call, &[]));
+        }
+        let shape_payload = shape.payload;
+        let payload = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", data_ptr as "void*", data_size as "size_t", dtype as "DataType", read_only as "bool", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    auto view = MakeSharedObject<NDArrayView>(dtype, shape_payload, data_ptr, data_size * DataTypeSize(dtype), DeviceDescriptor::CPUDevice(), read_only);
+                    return MakeSharedObject<CntkRsBorrowedValue>(view);
+                });
+            });
+            check_error(error_p, "Value::Value", &[])?;
+            payload
+        };
+        Ok(BorrowedValue { value: Value { payload }, _data: PhantomData })
+    }
+
+    /// Same as `borrow_slice`, but data contains batch of samples of given sample shape (like in `batch_from_vec`).
+    pub fn borrow_batch<'a, T: CntkElement>(shape: &Shape, data: &'a [T]) -> BorrowedValue<'a> {
+        Value::try_borrow_batch(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_borrow_batch<'a, T: CntkElement>(shape: &Shape, data: &'a [T]) -> Result<BorrowedValue<'a>, Error> {
+        let batch_shape = batch_shape(shape, data.len(), "Value::borrow_batch")?;
+        Value::try_borrow_slice(&batch_shape, data)
+    }
+
+    /// Same as `borrow_slice_mut`, but buffer holds batch of samples of given sample shape.
+    pub fn borrow_batch_mut<'a, T: CntkElement>(shape: &Shape, data: &'a mut [T]) -> BorrowedValue<'a> {
+        Value::try_borrow_batch_mut(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_borrow_batch_mut<'a, T: CntkElement>(shape: &Shape, data: &'a mut [T]) -> Result<BorrowedValue<'a>, Error> {
+        let batch_shape = batch_shape(shape, data.len(), "Value::borrow_batch_mut")?;
+        Value::try_borrow_slice_mut(&batch_shape, data)
+    }
+
+    /// Same as `borrow_slice` for ndarray. Shape of ndarray must be reversed `shape` and, since data cannot be
+    /// repacked without copying, it must have standard (row-major, contiguous) layout.
+    pub fn borrow_ndarray<'a, T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &'a ArrayBase<S, D>) -> BorrowedValue<'a> {
+        Value::try_borrow_ndarray(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_borrow_ndarray<'a, T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &'a ArrayBase<S, D>) -> Result<BorrowedValue<'a>, Error> {
+        check_ndarray(shape, data, true, "Value::borrow_ndarray")?;
+        Value::try_borrow_slice(shape, contiguous_data(data, "Value::borrow_ndarray")?)
+    }
+
+    /// Same as `borrow_batch` for ndarray with standard layout. Batch axis is the first axis of ndarray.
+    pub fn borrow_batch_ndarray<'a, T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &'a ArrayBase<S, D>) -> BorrowedValue<'a> {
+        Value::try_borrow_batch_ndarray(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_borrow_batch_ndarray<'a, T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &'a ArrayBase<S, D>) -> Result<BorrowedValue<'a>, Error> {
+        check_ndarray(shape, data, false, "Value::borrow_batch_ndarray")?;
+        Value::try_borrow_batch(shape, contiguous_data(data, "Value::borrow_batch_ndarray")?)
     }
 
     pub fn one_hot_seq(shape: &Shape, seq: &[usize], device: DeviceDescriptor) -> Value {
+        Value::try_one_hot_seq(shape, seq, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_one_hot_seq(shape: &Shape, seq: &[usize], device: DeviceDescriptor) -> Result<Value, Error> {
         let data_ptr = seq.as_ptr();
         let data_size = seq.len();
         let shape_payload = shape.payload;
         let device_payload = device.payload;
         let payload = unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+            let mut error_p: *mut c_char = ptr::null_mut();
             let payload = cpp!([shape_payload as "NDShape", data_ptr as "size_t*", data_size as "size_t", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
-                try {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
                     vector<size_t> data(data_ptr, data_ptr + data_size);
                     return Value::Create<float>(shape_payload, { data }, device_payload);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Value::Create", &[])?;
             payload
         };
-        Value { payload }
+        Ok(Value { payload })
     }
 
-    fn batch_of_sequences(sizes_ptr: *const usize, n_batches: usize, data_ptr: *const *const f32, shape: &Shape, device: DeviceDescriptor) -> Value {
+    fn batch_of_sequences(sizes_ptr: *const usize, n_batches: usize, data_ptr: *const *const c_void, dtype: DataType, start_flags: &[bool], shape: &Shape, device: DeviceDescriptor) -> Result<Value, Error> {
+        if start_flags.len() != n_batches {
+            return Err(Error::new(&format!("Number of sequence start flags {} does not match number of sequences {}", start_flags.len(), n_batches), "Value::CreateBatchOfSequences", &[]));
+        }
+        let flags_ptr = start_flags.as_ptr();
         let shape_payload = shape.payload;
         let device_payload = device.payload;
         let payload = unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
-            let payload = cpp!([shape_payload as "NDShape", sizes_ptr as "size_t*", n_batches as "size_t", data_ptr as "float**", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
-                try {
-                    vector<vector<float>> data;
-                    for (size_t i = 0; i < n_batches; i++) {
-                        data.push_back(vector<float>(data_ptr[i], data_ptr[i] + sizes_ptr[i]));
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", sizes_ptr as "size_t*", n_batches as "size_t", data_ptr as "const void* const*", dtype as "DataType", flags_ptr as "const bool*", device_payload as "DeviceDescriptor", mut error_p as "char*" ] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    vector<bool> flags(flags_ptr, flags_ptr + n_batches);
+                    if (dtype == DataType::Double) {
+                        return Value::CreateBatchOfSequences(shape_payload, cntk_rs_vectors<double>(data_ptr, sizes_ptr, n_batches), flags, device_payload, true);
                     }
-                    return Value::CreateBatchOfSequences(shape_payload, data, device_payload, true);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+                    return Value::CreateBatchOfSequences(shape_payload, cntk_rs_vectors<float>(data_ptr, sizes_ptr, n_batches), flags, device_payload, true);
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Value::CreateBatchOfSequences", &[])?;
             payload
         };
-        Value { payload }
+        Ok(Value { payload })
+    }
+
+    pub fn batch_of_sequences_from_vec<U: Borrow<[f32]>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Value {
+        Value::batch_of_sequences_from_vec_of::<f32, U>(shape, seqs, device)
+    }
+
+    pub fn batch_of_sequences_from_vec_of<T: CntkElement, U: Borrow<[T]>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Value {
+        Value::try_batch_of_sequences_from_vec_of(shape, seqs, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_of_sequences_from_vec<U: Borrow<[f32]>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_of_sequences_from_vec_of::<f32, U>(shape, seqs, device)
+    }
+
+    pub fn try_batch_of_sequences_from_vec_of<T: CntkElement, U: Borrow<[T]>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_of_sequences_from_vec_with_start_flags_of(shape, seqs, &vec![true; seqs.len()], device)
     }
 
-    pub fn batch_of_sequences_from_vec<T: Borrow<[f32]>>(shape: &Shape, seqs: &[T], device: DeviceDescriptor) -> Value {
+    /// Same as `batch_of_sequences_from_vec`, but sequences with start flag set to false are
+    /// continuations of sequences from previous minibatch (e.g. for truncated BPTT over long streams).
+    pub fn batch_of_sequences_from_vec_with_start_flags<U: Borrow<[f32]>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Value {
+        Value::batch_of_sequences_from_vec_with_start_flags_of::<f32, U>(shape, seqs, start_flags, device)
+    }
+
+    pub fn batch_of_sequences_from_vec_with_start_flags_of<T: CntkElement, U: Borrow<[T]>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Value {
+        Value::try_batch_of_sequences_from_vec_with_start_flags_of(shape, seqs, start_flags, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_of_sequences_from_vec_with_start_flags<U: Borrow<[f32]>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_of_sequences_from_vec_with_start_flags_of::<f32, U>(shape, seqs, start_flags, device)
+    }
+
+    pub fn try_batch_of_sequences_from_vec_with_start_flags_of<T: CntkElement, U: Borrow<[T]>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Result<Value, Error> {
         let sizes = seqs.iter().map(|x| x.borrow().len()).collect::<Vec<usize>>();
         let sizes_ptr = sizes.as_ptr();
-        let seqs_ptr = seqs.iter().map(|x| x.borrow().as_ptr()).collect::<Vec<_>>();
+        let seqs_ptr = seqs.iter().map(|x| x.borrow().as_ptr() as *const c_void).collect::<Vec<_>>();
         let data_ptr = seqs_ptr.as_ptr();
         let n_batches = seqs.len();
-        Value::batch_of_sequences(sizes_ptr, n_batches, data_ptr, shape, device)
+        Value::batch_of_sequences(sizes_ptr, n_batches, data_ptr, T::data_type(), start_flags, shape, device)
+    }
+
+    pub fn batch_of_sequences_from_ndarray<S: Data<Elem=f32>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Value {
+        Value::batch_of_sequences_from_ndarray_of::<f32, S, D, U>(shape, seqs, device)
+    }
+
+    pub fn batch_of_sequences_from_ndarray_of<T: CntkElement, S: Data<Elem=T>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Value {
+        Value::try_batch_of_sequences_from_ndarray_of(shape, seqs, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_of_sequences_from_ndarray<S: Data<Elem=f32>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_of_sequences_from_ndarray_of::<f32, S, D, U>(shape, seqs, device)
+    }
+
+    pub fn try_batch_of_sequences_from_ndarray_of<T: CntkElement, S: Data<Elem=T>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_of_sequences_from_ndarray_with_start_flags_of(shape, seqs, &vec![true; seqs.len()], device)
     }
 
-    pub fn batch_of_sequences_from_ndarray<S: Data<Elem=f32>, D: Dimension, T: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[T], device: DeviceDescriptor) -> Value {
-        let expected_shape = shape.to_vec_reversed();
+    pub fn batch_of_sequences_from_ndarray_with_start_flags<S: Data<Elem=f32>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Value {
+        Value::batch_of_sequences_from_ndarray_with_start_flags_of::<f32, S, D, U>(shape, seqs, start_flags, device)
+    }
+
+    pub fn batch_of_sequences_from_ndarray_with_start_flags_of<T: CntkElement, S: Data<Elem=T>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Value {
+        Value::try_batch_of_sequences_from_ndarray_with_start_flags_of(shape, seqs, start_flags, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_of_sequences_from_ndarray_with_start_flags<S: Data<Elem=f32>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Result<Value, Error> {
+        Value::try_batch_of_sequences_from_ndarray_with_start_flags_of::<f32, S, D, U>(shape, seqs, start_flags, device)
+    }
+
+    pub fn try_batch_of_sequences_from_ndarray_with_start_flags_of<T: CntkElement, S: Data<Elem=T>, D: Dimension, U: Borrow<ArrayBase<S, D>>>(shape: &Shape, seqs: &[U], start_flags: &[bool], device: DeviceDescriptor) -> Result<Value, Error> {
         for seq in seqs {
-            let data = seq.borrow();
-            assert!(data.is_standard_layout(), "CNTK only supports NDArrays with standard layout");
-            let data_shape = data.shape();
-            assert!(data_shape.len() >= expected_shape.len());
-            assert!(expected_shape == &data_shape[data_shape.len() - expected_shape.len()..]);
+            check_ndarray(shape, seq.borrow(), false, "Value::batch_of_sequences_from_ndarray_with_start_flags")?;
         }
 
-        let sizes = seqs.iter().map(|x| x.borrow().len()).collect::<Vec<usize>>();
+        let seqs = seqs.iter().map(|x| ndarray_data(x.borrow())).collect::<Vec<_>>();
+        let sizes = seqs.iter().map(|x| x.len()).collect::<Vec<usize>>();
         let sizes_ptr = sizes.as_ptr();
-        let seqs_ptr = seqs.iter().map(|x| x.borrow().as_ptr()).collect::<Vec<_>>();
+        let seqs_ptr = seqs.iter().map(|x| x.as_ptr() as *const c_void).collect::<Vec<_>>();
         let data_ptr = seqs_ptr.as_ptr();
         let n_batches = seqs.len();
-        Value::batch_of_sequences(sizes_ptr, n_batches, data_ptr, shape, device)
+        Value::batch_of_sequences(sizes_ptr, n_batches, data_ptr, T::data_type(), start_flags, shape, device)
     }
 
     pub fn batch_of_one_hot_sequences<T: Borrow<[usize]>>(shape: &Shape, seqs: &[T], device: DeviceDescriptor) -> Value {
+        Value::try_batch_of_one_hot_sequences(shape, seqs, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_batch_of_one_hot_sequences<T: Borrow<[usize]>>(shape: &Shape, seqs: &[T], device: DeviceDescriptor) -> Result<Value, Error> {
         let sizes = seqs.iter().map(|x| x.borrow().len()).collect::<Vec<usize>>();
         let sizes_ptr = sizes.as_ptr();
         let seqs_ptr = seqs.iter().map(|x| x.borrow().as_ptr()).collect::<Vec<_>>();
@@ -238,54 +537,236 @@ impl Value {
         let shape_payload = shape.payload;
         let device_payload = device.payload;
         let payload = unsafe {
-            let mut error_p: *mut i8 = ptr::null_mut();
+            let mut error_p: *mut c_char = ptr::null_mut();
             let payload = cpp!([shape_payload as "NDShape", sizes_ptr as "size_t*", n_batches as "size_t", data_ptr as "size_t**", device_payload as "DeviceDescriptor", mut error_p as "char*"] -> ValueInner as "ValuePtr" {
-                try {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
                     vector<vector<size_t>> data;
                     for (size_t i = 0; i < n_batches; i++) {
                         data.push_back(vector<size_t>(data_ptr[i], data_ptr[i] + sizes_ptr[i]));
                     }
                     return Value::Create<float>(shape_payload, data, device_payload, true);
-                } catch (std::exception& e) {
-                    auto what = e.what();
-                    error_p = new char[strlen(what)+1];
-                    strcpy(error_p, what);
-                    return nullptr;
-                }
+                });
             });
-            if !error_p.is_null() {
-                let msg = CStr::from_ptr(error_p).to_str().unwrap();
-                panic!("{}", msg);
-            }
+            check_error(error_p, "Value::Create", &[])?;
             payload
         };
-        Value { payload }
+        Ok(Value { payload })
     }
 
-    pub fn to_vec(&self) -> Vec<f32> {
+    /// Creates Value from CSC data. Shape contains sample shape followed by sequence and batch axes
+    /// (same as in `from_vec`).
+    pub fn from_csc<T: CntkElement>(shape: &Shape, data: &SparseCsc<T>, device: DeviceDescriptor) -> Value {
+        Value::try_from_csc(shape, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_csc<T: CntkElement>(shape: &Shape, data: &SparseCsc<T>, device: DeviceDescriptor) -> Result<Value, Error> {
+        check_csc(data, shape, 1, "Value::from_csc")?;
+        let col_starts_ptr = data.col_starts.as_ptr();
+        let row_indices_ptr = data.row_indices.as_ptr();
+        let values_ptr = data.values.as_ptr() as *const c_void;
+        let nnz = data.values.len();
+        let dtype = T::data_type();
+        let shape_payload = shape.payload;
+        let device_payload = device.payload;
+        let payload = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", col_starts_ptr as "const int*", row_indices_ptr as "const int*", values_ptr as "const void*", nnz as "size_t", dtype as "DataType",
+                                device_payload as "DeviceDescriptor", mut error_p as "char*"] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    if (dtype == DataType::Double) {
+                        return MakeSharedObject<Value>(MakeSharedObject<NDArrayView>(shape_payload, col_starts_ptr, row_indices_ptr, static_cast<const double*>(values_ptr), nnz, device_payload));
+                    }
+                    return MakeSharedObject<Value>(MakeSharedObject<NDArrayView>(shape_payload, col_starts_ptr, row_indices_ptr, static_cast<const float*>(values_ptr), nnz, device_payload));
+                });
+            });
+            check_error(error_p, "Value::Value", &[])?;
+            payload
+        };
+        Ok(Value { payload })
+    }
+
+    /// Creates sequence of `sequence_length` sparse samples of given sample shape from CSC data.
+    pub fn sequence_from_csc<T: CntkElement>(shape: &Shape, sequence_length: usize, data: &SparseCsc<T>, device: DeviceDescriptor) -> Value {
+        Value::try_sequence_from_csc(shape, sequence_length, data, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_sequence_from_csc<T: CntkElement>(shape: &Shape, sequence_length: usize, data: &SparseCsc<T>, device: DeviceDescriptor) -> Result<Value, Error> {
+        check_csc(data, shape, sequence_length, "Value::sequence_from_csc")?;
+        let col_starts_ptr = data.col_starts.as_ptr();
+        let row_indices_ptr = data.row_indices.as_ptr();
+        let values_ptr = data.values.as_ptr() as *const c_void;
+        let nnz = data.values.len();
+        let dtype = T::data_type();
+        let shape_payload = shape.payload;
+        let device_payload = device.payload;
+        let payload = unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([shape_payload as "NDShape", sequence_length as "size_t", col_starts_ptr as "const int*", row_indices_ptr as "const int*", values_ptr as "const void*", nnz as "size_t", dtype as "DataType",
+                                device_payload as "DeviceDescriptor", mut error_p as "char*"] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&]() -> ValuePtr {
+                    if (dtype == DataType::Double) {
+                        return Value::CreateSequence(shape_payload, sequence_length, col_starts_ptr, row_indices_ptr, static_cast<const double*>(values_ptr), nnz, device_payload);
+                    }
+                    return Value::CreateSequence(shape_payload, sequence_length, col_starts_ptr, row_indices_ptr, static_cast<const float*>(values_ptr), nnz, device_payload);
+                });
+            });
+            check_error(error_p, "Value::CreateSequence", &[])?;
+            payload
+        };
+        Ok(Value { payload })
+    }
+
+    pub(super) fn is_null(&self) -> bool {
         let payload = self.payload;
-        let total_size = unsafe {
-            cpp!([payload as "ValuePtr"] -> usize as "size_t" {
-                return payload->Data()->Shape().TotalSize();
+        unsafe {
+            cpp!([payload as "ValuePtr"] -> bool as "bool" {
+                return payload == nullptr;
             })
-        };
-        let mut buffer: Vec<f32> = Vec::with_capacity(total_size);
-        unsafe { buffer.set_len(total_size); }
-        let data = unsafe {
-            cpp!([payload as "ValuePtr"] -> *const f32 as "const float*" {
-              return payload->Data()->DataBuffer<float>();
+        }
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "ValuePtr"] -> bool as "bool" {
+                return payload->IsSparse();
             })
-        };
+        }
+    }
+
+    /// Copies sparse data out of the Value without densifying it.
+    pub fn to_csc(&self) -> SparseCsc<f32> {
+        self.to_csc_of::<f32>()
+    }
+
+    /// Copies sparse data out of the Value. Panics if the Value is not sparse or `T` does not match its data type.
+    pub fn to_csc_of<T: CntkElement>(&self) -> SparseCsc<T> {
+        self.try_to_csc_of::<T>().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_to_csc_of<T: CntkElement>(&self) -> Result<SparseCsc<T>, Error> {
+        if !self.is_sparse() {
+            return Err(Error::new("Value is not sparse", "Value::to_csc", &[]));
+        }
+        if self.data_type() != T::data_type() {
+            return Err(Error::new("Value data type does not match requested element type", "Value::to_csc", &[]));
+        }
+        let payload = self.payload;
+        let dtype = T::data_type();
+        let num_columns = num_columns(&self.shape(), 1)?;
+        let null_int: *mut i32 = ptr::null_mut();
+        let null_values: *mut c_void = ptr::null_mut();
         unsafe {
-            ptr::copy(data, buffer.as_mut_ptr(), total_size);
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let nnz = cpp!([payload as "ValuePtr", dtype as "DataType", num_columns as "size_t", null_int as "int*", null_values as "void*", mut error_p as "char*"] -> usize as "size_t" {
+                return cntk_rs_guard(error_p, [&]() -> size_t {
+                    if (dtype == DataType::Double) {
+                        return cntk_rs_copy_csc<double>(payload->Data(), num_columns, null_int, null_int, null_values);
+                    }
+                    return cntk_rs_copy_csc<float>(payload->Data(), num_columns, null_int, null_int, null_values);
+                });
+            });
+            check_error(error_p, "NDArrayView::SparseCSCDataBuffers", &[])?;
+
+            let mut data = SparseCsc { col_starts: vec![0; num_columns + 1], row_indices: vec![0; nnz], values: vec![T::default(); nnz] };
+            let col_starts_ptr = data.col_starts.as_mut_ptr();
+            let row_indices_ptr = data.row_indices.as_mut_ptr();
+            let values_ptr = data.values.as_mut_ptr() as *mut c_void;
+            cpp!([payload as "ValuePtr", dtype as "DataType", num_columns as "size_t", col_starts_ptr as "int*", row_indices_ptr as "int*", values_ptr as "void*", mut error_p as "char*"] {
+                cntk_rs_guard(error_p, [&]() -> size_t {
+                    if (dtype == DataType::Double) {
+                        return cntk_rs_copy_csc<double>(payload->Data(), num_columns, col_starts_ptr, row_indices_ptr, values_ptr);
+                    }
+                    return cntk_rs_copy_csc<float>(payload->Data(), num_columns, col_starts_ptr, row_indices_ptr, values_ptr);
+                });
+            });
+            check_error(error_p, "NDArrayView::SparseCSCDataBuffers", &[])?;
+            Ok(data)
         }
-        buffer
+    }
+
+    /// Copies data of the Value into `output`, which must have the same size. Panics if `T` does not match the data type of the Value.
+    pub fn copy_to_slice<T: CntkElement>(&self, output: &mut [T]) {
+        self.data().copy_to_slice(output)
+    }
+
+    pub fn try_copy_to_slice<T: CntkElement>(&self, output: &mut [T]) -> Result<(), Error> {
+        self.data().try_copy_to_slice(output)
+    }
+
+    /// Copies data of the Value into ndarray (with any memory layout) with reversed shape of the Value.
+    pub fn copy_to_ndarray<T: CntkElement, D: Dimension, S: DataMut<Elem=T>>(&self, output: &mut ArrayBase<S, D>) {
+        self.data().copy_to_ndarray(output)
+    }
+
+    pub fn try_copy_to_ndarray<T: CntkElement, D: Dimension, S: DataMut<Elem=T>>(&self, output: &mut ArrayBase<S, D>) -> Result<(), Error> {
+        self.data().try_copy_to_ndarray(output)
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.to_vec_of::<f32>()
+    }
+
+    /// Copies data out of the Value. Panics if `T` does not match the data type of the Value.
+    pub fn to_vec_of<T: CntkElement>(&self) -> Vec<T> {
+        self.data().to_vec_of::<T>()
+    }
+
+    pub fn try_to_vec(&self) -> Result<Vec<f32>, Error> {
+        self.try_to_vec_of::<f32>()
+    }
+
+    pub fn try_to_vec_of<T: CntkElement>(&self) -> Result<Vec<T>, Error> {
+        self.data().try_to_vec_of::<T>()
     }
 
     pub fn to_ndarray(&self) -> ArrayD<f32> {
-        let vec = self.to_vec();
-        let shape = self.shape().to_vec_reversed();
-        Array::from_shape_vec(shape, vec).unwrap()
+        self.to_ndarray_of::<f32>()
+    }
+
+    pub fn to_ndarray_of<T: CntkElement>(&self) -> ArrayD<T> {
+        self.data().to_ndarray_of::<T>()
+    }
+
+    /// Copies data out of the Value into ndarray with given axis order and memory layout (see `NDArrayLayout`).
+    pub fn to_ndarray_with_layout<T: CntkElement>(&self, layout: NDArrayLayout) -> ArrayD<T> {
+        self.data().to_ndarray_with_layout::<T>(layout)
+    }
+
+    /// Data of the Value (without copying)
+    pub fn data(&self) -> NDArrayView {
+        let payload = self.payload;
+        NDArrayView { payload: unsafe {
+            cpp!([payload as "ValuePtr"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return payload->Data();
+            })
+        }}
+    }
+
+    /// Creates Value referencing data of the NDArrayView (without copying)
+    pub fn from_nd_array_view(data: &NDArrayView) -> Value {
+        Value::try_from_nd_array_view(data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_nd_array_view(data: &NDArrayView) -> Result<Value, Error> {
+        let dpayload = data.payload;
+        Ok(Value { payload: unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let payload = cpp!([dpayload as "NDArrayViewPtr", mut error_p as "char*"] -> ValueInner as "ValuePtr" {
+                return cntk_rs_guard(error_p, [&] { return MakeSharedObject<Value>(dpayload); });
+            });
+            check_error(error_p, "Value::Value", &[])?;
+            payload
+        }})
+    }
+
+    pub fn data_type(&self) -> DataType {
+        let payload = self.payload;
+        DataType::from_raw(unsafe {
+            cpp!([payload as "ValuePtr"] -> u32 as "unsigned int" {
+                return (unsigned int)payload->GetDataType();
+            })
+        })
     }
 
     pub fn shape(&self) -> Shape {
@@ -296,6 +777,236 @@ impl Value {
             })
         }}
     }
+
+    fn has_mask(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "ValuePtr"] -> bool as "bool" {
+                return payload->Mask() != nullptr;
+            })
+        }
+    }
+
+    /// Returns (maximal sequence length, number of sequences). Values without mask are expected to
+    /// have sequence and batch axes as the last two axes of their shape.
+    fn sequence_layout(&self) -> (usize, usize) {
+        let payload = self.payload;
+        if self.has_mask() {
+            let mask_shape = Shape { payload: unsafe {
+                cpp!([payload as "ValuePtr"] -> ShapeInner as "NDShape" {
+                    return payload->Mask()->Shape();
+                })
+            }};
+            (mask_shape.get(0), mask_shape.get(1))
+        } else {
+            let shape = self.shape().to_vec();
+            match shape.len() {
+                0 => (1, 1),
+                1 => (shape[0], 1),
+                n => (shape[n - 2], shape[n - 1]),
+            }
+        }
+    }
+
+    pub fn num_sequences(&self) -> usize {
+        self.sequence_layout().1
+    }
+
+    /// Mask of each step of every sequence (padded to the length of the longest sequence).
+    /// Values without mask have all sequences of full length, each starting with `SequenceBegin`.
+    pub fn mask(&self) -> Vec<Vec<MaskKind>> {
+        self.try_mask().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fails for masks not stored on CPU.
+    pub fn try_mask(&self) -> Result<Vec<Vec<MaskKind>>, Error> {
+        let (max_len, num_seqs) = self.sequence_layout();
+        if !self.has_mask() {
+            let mut seq = vec![MaskKind::Valid; max_len];
+            if max_len > 0 {
+                seq[0] = MaskKind::SequenceBegin;
+            }
+            return Ok(vec![seq; num_seqs]);
+        }
+        let payload = self.payload;
+        let mut buffer: Vec<u8> = vec![0; max_len * num_seqs];
+        let buffer_len = buffer.len();
+        let mut buffer_ptr = buffer.as_mut_ptr();
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            cpp!([payload as "ValuePtr", mut buffer_ptr as "char*", buffer_len as "size_t", mut error_p as "char*"] {
+                cntk_rs_guard(error_p, [&] {
+                    auto mask = payload->Mask();
+                    auto data = (const char*)mask->DataBuffer();
+                    copy(data, data + min(mask->Shape().TotalSize(), buffer_len), buffer_ptr);
+                });
+            });
+            check_error(error_p, "Value::Mask", &[])?;
+        }
+        Ok(buffer.chunks(max_len.max(1)).take(num_seqs).map(|seq| seq.iter().map(|&x| match x {
+            1 => MaskKind::Valid,
+            2 => MaskKind::SequenceBegin,
+            _ => MaskKind::Invalid,
+        }).collect()).collect())
+    }
+
+    /// Number of valid (non-padding) steps of every sequence
+    pub fn sequence_lengths(&self) -> Vec<usize> {
+        self.mask().iter().map(|seq| seq.iter().filter(|&&x| x != MaskKind::Invalid).count()).collect()
+    }
+
+    /// Whether each sequence starts in this Value (false for continuations of sequences from previous minibatch)
+    pub fn sequence_start_flags(&self) -> Vec<bool> {
+        self.mask().iter().map(|seq| seq.first() == Some(&MaskKind::SequenceBegin)).collect()
+    }
+
+    /// Splits dense Value into one ndarray per sequence, with padding removed.
+    /// Each ndarray has shape [sequence length, reversed sample shape...].
+    pub fn to_sequences(&self) -> Vec<ArrayD<f32>> {
+        self.to_sequences_of::<f32>()
+    }
+
+    pub fn to_sequences_of<T: CntkElement>(&self) -> Vec<ArrayD<T>> {
+        let (max_len, _) = self.sequence_layout();
+        let sample_shape = self.sample_shape_reversed();
+        let sample_size = sample_shape.iter().product::<usize>();
+        let data = self.to_vec_of::<T>();
+        self.sequence_lengths().into_iter().enumerate().map(|(i, len)| {
+            let start = i * max_len * sample_size;
+            let mut shape = vec![len];
+            shape.extend(sample_shape.iter().cloned());
+            Array::from_shape_vec(shape, data[start..start + len * sample_size].to_vec()).unwrap()
+        }).collect()
+    }
+
+    /// Converts every step of every sequence into index of its largest element, with padding removed.
+    /// For one-hot (sparse or dense) Values this is inverse of `batch_of_one_hot_sequences`.
+    pub fn to_one_hot_sequences(&self) -> Vec<Vec<usize>> {
+        match self.data_type() {
+            DataType::Double => self.one_hot_sequences::<f64>(),
+            _ => self.one_hot_sequences::<f32>(),
+        }
+    }
+
+    fn one_hot_sequences<T: CntkElement>(&self) -> Vec<Vec<usize>> {
+        let (max_len, _) = self.sequence_layout();
+        let lengths = self.sequence_lengths();
+        let argmax = |values: &mut dyn Iterator<Item=(usize, T)>| {
+            values.fold((0, None), |(best, best_value): (usize, Option<f64>), (index, value)| {
+                let value: f64 = value.into();
+                match best_value {
+                    Some(x) if x >= value => (best, best_value),
+                    _ => (index, Some(value)),
+                }
+            }).0
+        };
+        if self.is_sparse() {
+            let data = self.to_csc_of::<T>();
+            lengths.into_iter().enumerate().map(|(i, len)| (0..len).map(|step| {
+                let column = i * max_len + step;
+                let (start, end) = (data.col_starts[column] as usize, data.col_starts[column + 1] as usize);
+                if start == end {
+                    0
+                } else {
+                    argmax(&mut (start..end).map(|x| (data.row_indices[x] as usize, data.values[x])))
+                }
+            }).collect()).collect()
+        } else {
+            let sample_size = self.sample_shape_reversed().iter().product::<usize>();
+            let data = self.to_vec_of::<T>();
+            lengths.into_iter().enumerate().map(|(i, len)| (0..len).map(|step| {
+                let start = (i * max_len + step) * sample_size;
+                argmax(&mut data[start..start + sample_size].iter().cloned().enumerate())
+            }).collect()).collect()
+        }
+    }
+
+    fn sample_shape_reversed(&self) -> Vec<usize> {
+        let mut shape = self.shape().to_vec();
+        let sample_rank = shape.len().saturating_sub(2);
+        shape.truncate(sample_rank);
+        shape.reverse();
+        shape
+    }
+}
+
+/// Sample shape extended with sequence axis of length 1 and batch axis (same layout as created by CreateBatch).
+fn batch_shape(shape: &Shape, data_size: usize, call: &'static str) -> Result<Shape, Error> {
+    let sample_size = shape.try_total_size()?;
+    if sample_size == 0 || data_size % sample_size != 0 {
+        return Err(Error::new(&format!("Data size {} is not multiple of sample size {}", data_size, sample_size), call, &[]));
+    }
+    Ok(shape.append_shape(&Shape::new(vec!(1, data_size / sample_size))))
+}
+
+/// Number of CSC rows of shape (its first axis, 1 for scalars).
+fn num_rows(shape: &Shape) -> usize {
+    if shape.rank() == 0 { 1 } else { shape.get(0) }
+}
+
+/// Number of CSC columns of shape repeated `repeat` times (all axes except the first one are columns).
+fn num_columns(shape: &Shape, repeat: usize) -> Result<usize, Error> {
+    let rows = num_rows(shape);
+    if rows == 0 {
+        return Err(Error::new("Sparse data must have nonzero leading dimension", "Value::num_columns", &[]));
+    }
+    Ok(shape.try_total_size()? / rows * repeat)
+}
+
+/// Checks that CSC buffers are consistent with each other and with `shape` repeated `repeat` times,
+/// so that CNTK never reads outside of them.
+fn check_csc<T>(data: &SparseCsc<T>, shape: &Shape, repeat: usize, call: &'static str) -> Result<(), Error> {
+    let num_columns = num_columns(shape, repeat)?;
+    let num_rows = num_rows(shape);
+    if data.row_indices.len() != data.values.len() {
+        return Err(Error::new(&format!("Number of row indices {} does not match number of values {}", data.row_indices.len(), data.values.len()), call, &[]));
+    }
+    if data.col_starts.len() != num_columns + 1 {
+        return Err(Error::new(&format!("Expected {} column starts, got {}", num_columns + 1, data.col_starts.len()), call, &[]));
+    }
+    if data.col_starts[0] != 0 {
+        return Err(Error::new("First column start must be 0", call, &[]));
+    }
+    if data.col_starts.windows(2).any(|w| w[0] > w[1]) {
+        return Err(Error::new("Column starts must be non-decreasing", call, &[]));
+    }
+    if data.col_starts.last() != Some(&(data.values.len() as i32)) {
+        return Err(Error::new("Last column start must be equal to number of values", call, &[]));
+    }
+    if let Some(row) = data.row_indices.iter().find(|&&row| row < 0 || row as usize >= num_rows) {
+        return Err(Error::new(&format!("Row index {} out of range for {} rows", row, num_rows), call, &[]));
+    }
+    Ok(())
+}
+
+/// Checks that shape of ndarray matches (reversed) CNTK shape.
+/// If `exact` is false, ndarray can have extra leading (batch and sequence) axes.
+pub(super) fn check_ndarray<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, data: &ArrayBase<S, D>, exact: bool, call: &'static str) -> Result<(), Error> {
+    let expected_shape = shape.to_vec_reversed();
+    let data_shape = data.shape();
+    let matches = if exact {
+        data_shape == &expected_shape[..]
+    } else {
+        data_shape.len() >= expected_shape.len() && expected_shape == &data_shape[data_shape.len() - expected_shape.len()..]
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(Error::new(&format!("NDArray shape {:?} does not match expected shape {:?}", data_shape, expected_shape), call, &[]))
+    }
+}
+
+/// Data of ndarray in row-major order of its axes (which is CNTK column-major order of reversed axes).
+/// Standard layout arrays are used directly, other strides (transposed views, slices, Fortran order) are packed into a copy.
+pub(super) fn ndarray_data<'a, T: CntkElement, D: Dimension, S: Data<Elem=T>>(data: &'a ArrayBase<S, D>) -> Cow<'a, [T]> {
+    match data.as_slice() {
+        Some(slice) => Cow::Borrowed(slice),
+        None => Cow::Owned(data.iter().cloned().collect()),
+    }
+}
+
+fn contiguous_data<'a, T: CntkElement, D: Dimension, S: Data<Elem=T>>(data: &'a ArrayBase<S, D>, call: &'static str) -> Result<&'a [T], Error> {
+    data.as_slice().ok_or_else(|| Error::new("Borrowed NDArray must have standard layout", call, &[]))
 }
 
 impl Drop for Value {
@@ -307,4 +1018,59 @@ impl Drop for Value {
             })
         };
     }
-}
\ No newline at end of file
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        let xpayload = self.payload;
+        let payload = unsafe {
+            cpp!([xpayload as "ValuePtr"] -> ValueInner as "ValuePtr" {
+                return xpayload;
+            })
+        };
+        Value {payload}
+    }
+}
+
+impl<'a> BorrowedValue<'a> {
+    pub fn shape(&self) -> Shape {
+        self.value.shape()
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.value.data_type()
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.value.to_vec()
+    }
+
+    pub fn to_vec_of<T: CntkElement>(&self) -> Vec<T> {
+        self.value.to_vec_of::<T>()
+    }
+
+    pub fn copy_to_slice<T: CntkElement>(&self, output: &mut [T]) {
+        self.value.copy_to_slice(output)
+    }
+
+    pub fn try_copy_to_slice<T: CntkElement>(&self, output: &mut [T]) -> Result<(), Error> {
+        self.value.try_copy_to_slice(output)
+    }
+
+    pub(super) fn payload(&self) -> ValueInner {
+        self.value.payload
+    }
+}
+
+impl<'a> Drop for BorrowedValue<'a> {
+    fn drop(&mut self) {
+        let payload = self.value.payload;
+        unsafe {
+            cpp!([payload as "ValuePtr"] {
+                auto borrowed = static_pointer_cast<CntkRsBorrowedValue>(payload);
+                borrowed->erased = true;
+                borrowed->Erase();
+            })
+        };
+    }
+}
@@ -1,9 +1,17 @@
-use axis::Axis;
+use axis::{Axis, AxisInner};
 use shape::{Shape, ShapeInner};
+use nd_array_view::{NDArrayView, NDArrayViewInner};
 use device::DeviceDescriptor;
-use function::Function;
+use function::{Function, FunctionInner};
+use std::hash::{Hash, Hasher};
+use error::{Error, check_error};
+use data_type::{DataType, CntkElement};
 use std::borrow::Borrow;
+use std::ops::Deref;
 use std::ptr;
+use value::{check_ndarray, ndarray_data};
+use ndarray::{ArrayD, ArrayBase, Data, Dimension};
+use std::os::raw::{c_char, c_void};
 
 cpp! {{
   #include <CNTKLibrary.h>
@@ -86,6 +94,71 @@ impl ParameterInitializer {
     }
 }
 
+/// Kind of variable (mirrors CNTK VariableKind enum)
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariableKind {
+    Input = 0,
+    Output = 1,
+    Parameter = 2,
+    Constant = 3,
+    Placeholder = 4,
+}
+
+/// Builder of input variables, created by `Variable::input`.
+///
+/// By default the variable is dense, f32, without name, does not need gradient and has default sequence and batch axes.
+pub struct InputVariableBuilder {
+    shape: Shape,
+    name: String,
+    is_sparse: bool,
+    needs_gradient: bool,
+    dynamic_axes: Option<Vec<Axis>>,
+    dtype: DataType,
+}
+
+impl InputVariableBuilder {
+    pub fn name(mut self, name: &str) -> InputVariableBuilder {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn sparse(mut self) -> InputVariableBuilder {
+        self.is_sparse = true;
+        self
+    }
+
+    pub fn needs_gradient(mut self) -> InputVariableBuilder {
+        self.needs_gradient = true;
+        self
+    }
+
+    /// Replaces default dynamic axes (e.g. empty slice creates variable without sequence and batch axes).
+    pub fn dynamic_axes<A: Borrow<Axis>>(mut self, dynamic_axes: &[A]) -> InputVariableBuilder {
+        self.dynamic_axes = Some(dynamic_axes.iter().map(|x| x.borrow().clone()).collect());
+        self
+    }
+
+    /// Uses `axis` as sequence axis followed by default batch axis.
+    pub fn sequence_axis(self, axis: &Axis) -> InputVariableBuilder {
+        self.dynamic_axes(&[axis.clone(), Axis::default_batch_axis()])
+    }
+
+    pub fn dtype(mut self, dtype: DataType) -> InputVariableBuilder {
+        self.dtype = dtype;
+        self
+    }
+
+    pub fn build(&self) -> InputVariable {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_build(&self) -> Result<InputVariable, Error> {
+        let axis_payloads = self.dynamic_axes.as_ref().map(|axes| axes.iter().map(|x| x.payload).collect::<Vec<_>>());
+        Variable::try_create_input(&self.shape, self.is_sparse, self.dtype, self.needs_gradient, &self.name, axis_payloads.as_ref().map(|x| &x[..]))
+    }
+}
+
 pub(super) type VariableInner = [u64; 5usize];
 
 #[derive(Debug)]
@@ -94,111 +167,264 @@ pub struct Variable {
 }
 
 impl Variable {
-    pub fn create<A: Borrow<Axis>>(shape: &Shape, is_sparse: bool, needs_gradient: bool, name: &str, dynamic_axes: &[A]) -> Variable {
+    pub fn create<A: Borrow<Axis>>(shape: &Shape, is_sparse: bool, needs_gradient: bool, name: &str, dynamic_axes: &[A]) -> InputVariable {
+        Variable::create_of::<f32, A>(shape, is_sparse, needs_gradient, name, dynamic_axes)
+    }
+
+    pub fn create_of<T: CntkElement, A: Borrow<Axis>>(shape: &Shape, is_sparse: bool, needs_gradient: bool, name: &str, dynamic_axes: &[A]) -> InputVariable {
+        Variable::try_create_of::<T, A>(shape, is_sparse, needs_gradient, name, dynamic_axes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_create<A: Borrow<Axis>>(shape: &Shape, is_sparse: bool, needs_gradient: bool, name: &str, dynamic_axes: &[A]) -> Result<InputVariable, Error> {
+        Variable::try_create_of::<f32, A>(shape, is_sparse, needs_gradient, name, dynamic_axes)
+    }
+
+    pub fn try_create_of<T: CntkElement, A: Borrow<Axis>>(shape: &Shape, is_sparse: bool, needs_gradient: bool, name: &str, dynamic_axes: &[A]) -> Result<InputVariable, Error> {
+        let axis_payloads = dynamic_axes.iter().map(|x| x.borrow().payload).collect::<Vec<_>>();
+        Variable::try_create_input(shape, is_sparse, T::data_type(), needs_gradient, name, Some(&axis_payloads))
+    }
+
+    /// Starts building input variable, see `InputVariableBuilder`.
+    pub fn input(shape: &Shape) -> InputVariableBuilder {
+        InputVariableBuilder {
+            shape: shape.clone(),
+            name: String::new(),
+            is_sparse: false,
+            needs_gradient: false,
+            dynamic_axes: None,
+            dtype: DataType::Float,
+        }
+    }
+
+    fn try_create_input(shape: &Shape, is_sparse: bool, dtype: DataType, needs_gradient: bool, name: &str, dynamic_axes: Option<&[AxisInner]>) -> Result<InputVariable, Error> {
         let spayload = shape.payload;
         let name_ptr = name.as_ptr();
         let name_len = name.len();
-        let axis_payloads = dynamic_axes.iter().map(|x| x.borrow().payload).collect::<Vec<_>>();
-        let axis_ptr = axis_payloads.as_ptr();
-        let axis_len = axis_payloads.len();
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape", name_ptr as "char*", name_len as "size_t", is_sparse as "bool", needs_gradient as "bool", axis_ptr as "Axis*", axis_len as "size_t"] -> VariableInner as "Variable" {
-                string name(name_ptr, name_ptr + name_len);
-                wstring wname;
-                wname.assign(name.begin(), name.end());
-                return InputVariable(spayload, is_sparse, DataType::Float, needs_gradient, wname, vector<Axis>(axis_ptr, axis_ptr + axis_len));
-            })
-        }}
+        let default_axes = dynamic_axes.is_none();
+        let dynamic_axes = dynamic_axes.unwrap_or(&[]);
+        let axis_ptr = dynamic_axes.as_ptr();
+        let axis_len = dynamic_axes.len();
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", name_ptr as "char*", name_len as "size_t", is_sparse as "bool", dtype as "DataType", needs_gradient as "bool", default_axes as "bool", axis_ptr as "Axis*", axis_len as "size_t", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable {
+                    string name(name_ptr, name_ptr + name_len);
+                    wstring wname;
+                    wname.assign(name.begin(), name.end());
+                    auto axes = default_axes ? Axis::DefaultInputVariableDynamicAxes() : vector<Axis>(axis_ptr, axis_ptr + axis_len);
+                    return InputVariable(spayload, is_sparse, dtype, needs_gradient, wname, axes);
+                });
+            })};
+            check_error(error_p, "InputVariable", &[])?;
+            Ok(InputVariable(variable))
+        }
+    }
+
+    pub fn input_variable(shape: &Shape) -> InputVariable {
+        Variable::input_variable_of::<f32>(shape)
+    }
+
+    pub fn input_variable_of<T: CntkElement>(shape: &Shape) -> InputVariable {
+        Variable::try_input_variable_of::<T>(shape).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_input_variable(shape: &Shape) -> Result<InputVariable, Error> {
+        Variable::try_input_variable_of::<f32>(shape)
     }
 
-    pub fn input_variable(shape: &Shape) -> Variable {
+    pub fn try_input_variable_of<T: CntkElement>(shape: &Shape) -> Result<InputVariable, Error> {
+        let dtype = T::data_type();
         let spayload = shape.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape"] -> VariableInner as "Variable" {
-                return InputVariable(spayload, DataType::Float);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", dtype as "DataType", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return InputVariable(spayload, dtype); });
+            })};
+            check_error(error_p, "InputVariable", &[])?;
+            Ok(InputVariable(variable))
+        }
     }
 
-    pub fn sparse_input_variable(shape: &Shape) -> Variable {
+    pub fn sparse_input_variable(shape: &Shape) -> InputVariable {
+        Variable::try_sparse_input_variable(shape).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_sparse_input_variable(shape: &Shape) -> Result<InputVariable, Error> {
         let spayload = shape.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape"] -> VariableInner as "Variable" {
-                return InputVariable(spayload, true, DataType::Float);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return InputVariable(spayload, true, DataType::Float); });
+            })};
+            check_error(error_p, "InputVariable", &[])?;
+            Ok(InputVariable(variable))
+        }
+    }
+
+    pub fn input_variable_with_name(shape: &Shape, name: &str) -> InputVariable {
+        Variable::try_input_variable_with_name(shape, name).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn input_variable_with_name(shape: &Shape, name: &str) -> Variable {
+    pub fn try_input_variable_with_name(shape: &Shape, name: &str) -> Result<InputVariable, Error> {
         let spayload = shape.payload;
         let name_ptr = name.as_ptr();
         let name_len = name.len();
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape", name_ptr as "char*", name_len as "size_t"] -> VariableInner as "Variable" {
-                string name(name_ptr, name_ptr + name_len);
-                wstring wname;
-                wname.assign(name.begin(), name.end());
-                return InputVariable(spayload, DataType::Float, wname);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", name_ptr as "char*", name_len as "size_t", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable {
+                    string name(name_ptr, name_ptr + name_len);
+                    wstring wname;
+                    wname.assign(name.begin(), name.end());
+                    return InputVariable(spayload, DataType::Float, wname);
+                });
+            })};
+            check_error(error_p, "InputVariable", &[])?;
+            Ok(InputVariable(variable))
+        }
+    }
+
+    pub fn input_variable_with_gradient(shape: &Shape) -> InputVariable {
+        Variable::try_input_variable_with_gradient(shape).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn input_variable_with_gradient(shape: &Shape) -> Variable {
+    pub fn try_input_variable_with_gradient(shape: &Shape) -> Result<InputVariable, Error> {
         let spayload = shape.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape"] -> VariableInner as "Variable" {
-                return InputVariable(spayload, DataType::Float, true);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return InputVariable(spayload, DataType::Float, true); });
+            })};
+            check_error(error_p, "InputVariable", &[])?;
+            Ok(InputVariable(variable))
+        }
+    }
+
+    pub fn parameter(shape: &Shape, initializer: &ParameterInitializer, device: DeviceDescriptor) -> Parameter {
+        Variable::parameter_of::<f32>(shape, initializer, device)
+    }
+
+    pub fn parameter_of<T: CntkElement>(shape: &Shape, initializer: &ParameterInitializer, device: DeviceDescriptor) -> Parameter {
+        Variable::try_parameter_of::<T>(shape, initializer, device).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn parameter(shape: &Shape, initializer: &ParameterInitializer, device: DeviceDescriptor) -> Variable {
+    pub fn try_parameter(shape: &Shape, initializer: &ParameterInitializer, device: DeviceDescriptor) -> Result<Parameter, Error> {
+        Variable::try_parameter_of::<f32>(shape, initializer, device)
+    }
+
+    pub fn try_parameter_of<T: CntkElement>(shape: &Shape, initializer: &ParameterInitializer, device: DeviceDescriptor) -> Result<Parameter, Error> {
+        let dtype = T::data_type();
         let spayload = shape.payload;
         let dpayload = device.payload;
         let initializerpayload = initializer.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape", dpayload as "DeviceDescriptor", initializerpayload as "ParameterInitializer"] -> VariableInner as "Variable" {
-                return Parameter(spayload, DataType::Float, initializerpayload, dpayload);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", dtype as "DataType", dpayload as "DeviceDescriptor", initializerpayload as "ParameterInitializer", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return Parameter(spayload, dtype, initializerpayload, dpayload); });
+            })};
+            check_error(error_p, "Parameter", &[])?;
+            Ok(Parameter(variable))
+        }
+    }
+
+    pub fn placeholder(shape: &Shape) -> Placeholder {
+        Variable::try_placeholder(shape).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn placeholder(shape: &Shape) -> Variable {
+    pub fn try_placeholder(shape: &Shape) -> Result<Placeholder, Error> {
         let spayload = shape.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape"] -> VariableInner as "Variable" {
-                return PlaceholderVariable(spayload);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return PlaceholderVariable(spayload); });
+            })};
+            check_error(error_p, "PlaceholderVariable", &[])?;
+            Ok(Placeholder(variable))
+        }
     }
 
-    pub fn constant_scalar(value: f32) -> Variable {
-        Variable { payload: unsafe {
-            cpp!([value as "float"] -> VariableInner as "Variable" {
-                return Constant::Scalar(DataType::Float, value);
-            })
-        }}
+    pub fn constant_scalar(value: f32) -> Constant {
+        Variable::constant_scalar_of::<f32>(value)
+    }
+
+    pub fn constant_scalar_of<T: CntkElement>(value: T) -> Constant {
+        Variable::try_constant_scalar_of::<T>(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_constant_scalar(value: f32) -> Result<Constant, Error> {
+        Variable::try_constant_scalar_of::<f32>(value)
     }
 
-    pub fn constant_repeat(shape: &Shape, value: f32) -> Variable {
+    pub fn try_constant_scalar_of<T: CntkElement>(value: T) -> Result<Constant, Error> {
+        let dtype = T::data_type();
+        let value: f64 = value.into();
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([value as "double", dtype as "DataType", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return Constant::Scalar(dtype, value); });
+            })};
+            check_error(error_p, "Constant::Scalar", &[])?;
+            Ok(Constant(variable))
+        }
+    }
+
+    pub fn constant_repeat(shape: &Shape, value: f32) -> Constant {
+        Variable::constant_repeat_of::<f32>(shape, value)
+    }
+
+    pub fn constant_repeat_of<T: CntkElement>(shape: &Shape, value: T) -> Constant {
+        Variable::try_constant_repeat_of::<T>(shape, value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_constant_repeat(shape: &Shape, value: f32) -> Result<Constant, Error> {
+        Variable::try_constant_repeat_of::<f32>(shape, value)
+    }
+
+    pub fn try_constant_repeat_of<T: CntkElement>(shape: &Shape, value: T) -> Result<Constant, Error> {
+        let dtype = T::data_type();
+        let value: f64 = value.into();
         let spayload = shape.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape", value as "float"] -> VariableInner as "Variable" {
-                return Constant(spayload, DataType::Float, value);
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", value as "double", dtype as "DataType", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return Constant(spayload, dtype, value); });
+            })};
+            check_error(error_p, "Constant", &[])?;
+            Ok(Constant(variable))
+        }
+    }
+
+    pub fn constant_from_slice<T: CntkElement>(shape: &Shape, value: &[T], device: DeviceDescriptor) -> Constant {
+        Variable::try_constant_from_slice(shape, value, device).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn constant_from_slice(shape: &Shape, value: &[f32], device: DeviceDescriptor) -> Variable {
+    pub fn try_constant_from_slice<T: CntkElement>(shape: &Shape, value: &[T], device: DeviceDescriptor) -> Result<Constant, Error> {
+        let dtype = T::data_type();
         let spayload = shape.payload;
-        let value_ptr = value.as_ptr();
+        let value_ptr = value.as_ptr() as *const c_void;
         let value_len = value.len();
         let dpayload = device.payload;
-        Variable { payload: unsafe {
-            cpp!([spayload as "NDShape", value_ptr as "float*", value_len as "size_t", dpayload as "DeviceDescriptor"] -> VariableInner as "Variable" {
-                return Constant(MakeSharedObject<NDArrayView>(spayload, value_ptr, value_len, dpayload, true)->DeepClone());
-            })
-        }}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([spayload as "NDShape", dtype as "DataType", value_ptr as "const void*", value_len as "size_t", dpayload as "DeviceDescriptor", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable {
+                    auto view = MakeSharedObject<NDArrayView>(dtype, spayload, const_cast<void*>(value_ptr), value_len * DataTypeSize(dtype), dpayload, true);
+                    return Constant(view->DeepClone());
+                });
+            })};
+            check_error(error_p, "Constant", &[])?;
+            Ok(Constant(variable))
+        }
+    }
+
+    /// Creates constant with copy of ndarray data. Shape of ndarray must be reversed `shape`.
+    pub fn constant_from_ndarray<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, value: &ArrayBase<S, D>, device: DeviceDescriptor) -> Constant {
+        Variable::try_constant_from_ndarray(shape, value, device).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_constant_from_ndarray<T: CntkElement, D: Dimension, S: Data<Elem=T>>(shape: &Shape, value: &ArrayBase<S, D>, device: DeviceDescriptor) -> Result<Constant, Error> {
+        check_ndarray(shape, value, true, "Variable::constant_from_ndarray")?;
+        Variable::try_constant_from_slice(shape, &ndarray_data(value), device)
     }
 
     pub fn shape(&self) -> Shape {
@@ -219,6 +445,113 @@ impl Variable {
         }
     }
 
+    pub fn kind(&self) -> VariableKind {
+        let payload = self.payload;
+        let kind = unsafe {
+            cpp!([payload as "Variable"] -> u32 as "unsigned int" {
+                return (unsigned int)payload.Kind();
+            })
+        };
+        match kind {
+            0 => VariableKind::Input,
+            1 => VariableKind::Output,
+            2 => VariableKind::Parameter,
+            3 => VariableKind::Constant,
+            _ => VariableKind::Placeholder,
+        }
+    }
+
+    pub fn is_input(&self) -> bool {
+        self.kind() == VariableKind::Input
+    }
+
+    pub fn is_output(&self) -> bool {
+        self.kind() == VariableKind::Output
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.kind() == VariableKind::Constant
+    }
+
+    pub fn is_placeholder(&self) -> bool {
+        self.kind() == VariableKind::Placeholder
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "Variable"] -> bool as "bool" {
+                return payload.IsSparse();
+            })
+        }
+    }
+
+    pub fn needs_gradient(&self) -> bool {
+        let payload = self.payload;
+        unsafe {
+            cpp!([payload as "Variable"] -> bool as "bool" {
+                return payload.NeedsGradient();
+            })
+        }
+    }
+
+    pub fn dynamic_axes(&self) -> Vec<Axis> {
+        let payload = self.payload;
+        let num_axes = unsafe {
+            cpp!([payload as "Variable"] -> usize as "size_t" {
+                return payload.DynamicAxes().size();
+            })
+        };
+        let mut output: Vec<AxisInner> = Vec::with_capacity(num_axes);
+        unsafe {
+            output.set_len(num_axes);
+            let mut ptr = output.as_mut_ptr();
+            cpp!([payload as "Variable", mut ptr as "Axis*"] {
+                auto axes = payload.DynamicAxes();
+                for (size_t i = 0; i < axes.size(); i++) {
+                    ::new (&ptr[i]) Axis(axes[i]);
+                }
+            })
+        }
+        output.into_iter().map(|x| Axis {payload: x}).collect::<Vec<Axis>>()
+    }
+
+    /// Function producing this variable (only output variables have owner)
+    pub fn owner(&self) -> Option<Function> {
+        if !self.is_output() {
+            return None;
+        }
+        let payload = self.payload;
+        Some(Function { payload: unsafe {
+            cpp!([payload as "Variable"] -> FunctionInner as "FunctionPtr" {
+                return payload.Owner();
+            })
+        }})
+    }
+
+    /// Unique identifier of the variable (generated by CNTK)
+    pub fn uid(&self) -> String {
+        let payload = self.payload;
+        let uid_size = unsafe {
+            cpp!([payload as "Variable"] -> usize as "size_t" {
+                auto wuid = payload.Uid();
+                string uid(wuid.begin(), wuid.end());
+                return uid.size();
+            })
+        };
+        let mut bytes = Vec::with_capacity(uid_size);
+        unsafe {
+            bytes.set_len(uid_size);
+            let mut ptr = bytes.as_mut_ptr();
+            cpp!([payload as "Variable", mut ptr as "char*"] {
+                auto wuid = payload.Uid();
+                string uid(wuid.begin(), wuid.end());
+                copy(uid.begin(), uid.end(), ptr);
+            })
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
     pub fn name(&self) -> String {
         let payload = self.payload;
         let name_size = unsafe {
@@ -242,64 +575,76 @@ impl Variable {
     }
 
     pub fn normal_random(x: &Shape, mean: f64, scale: f64) -> Variable {
+        Variable::try_normal_random(x, mean, scale).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_normal_random(x: &Shape, mean: f64, scale: f64) -> Result<Variable, Error> {
         let xpayload = x.payload;
-        let payload = unsafe {
-            cpp!([xpayload as "NDShape", mean as "double", scale as "double"] -> VariableInner as "Variable" {
-            return NormalRandom(xpayload, DataType::Float, mean, scale);
-        })
-        };
-        Variable {payload}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([xpayload as "NDShape", mean as "double", scale as "double", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return NormalRandom(xpayload, DataType::Float, mean, scale); });
+            })};
+            check_error(error_p, "NormalRandom", &[])?;
+            Ok(variable)
+        }
     }
 
     pub fn bernoulli_random(x: &Shape, mean: f64) -> Variable {
+        Variable::try_bernoulli_random(x, mean).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_bernoulli_random(x: &Shape, mean: f64) -> Result<Variable, Error> {
         let xpayload = x.payload;
-        let payload = unsafe {
-            cpp!([xpayload as "NDShape", mean as "double"] -> VariableInner as "Variable" {
-            return BernoulliRandom(xpayload, DataType::Float, mean);
-        })
-        };
-        Variable {payload}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([xpayload as "NDShape", mean as "double", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return BernoulliRandom(xpayload, DataType::Float, mean); });
+            })};
+            check_error(error_p, "BernoulliRandom", &[])?;
+            Ok(variable)
+        }
     }
 
     pub fn uniform_random(x: &Shape, low: f64, high: f64) -> Variable {
+        Variable::try_uniform_random(x, low, high).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_uniform_random(x: &Shape, low: f64, high: f64) -> Result<Variable, Error> {
         let xpayload = x.payload;
-        let payload = unsafe {
-            cpp!([xpayload as "NDShape", low as "double", high as "double"] -> VariableInner as "Variable" {
-            return UniformRandom(xpayload, DataType::Float, low, high);
-        })
-        };
-        Variable {payload}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([xpayload as "NDShape", low as "double", high as "double", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return UniformRandom(xpayload, DataType::Float, low, high); });
+            })};
+            check_error(error_p, "UniformRandom", &[])?;
+            Ok(variable)
+        }
     }
 
     pub fn gumbel_random(x: &Shape, loc: f64, scale: f64) -> Variable {
+        Variable::try_gumbel_random(x, loc, scale).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_gumbel_random(x: &Shape, loc: f64, scale: f64) -> Result<Variable, Error> {
         let xpayload = x.payload;
-        let payload = unsafe {
-            cpp!([xpayload as "NDShape", loc as "double", scale as "double"] -> VariableInner as "Variable" {
-            return GumbelRandom(xpayload, DataType::Float, loc, scale);
-        })
-        };
-        Variable {payload}
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let variable = Variable { payload: cpp!([xpayload as "NDShape", loc as "double", scale as "double", mut error_p as "char*"] -> VariableInner as "Variable" {
+                return cntk_rs_guard(error_p, [&]() -> Variable { return GumbelRandom(xpayload, DataType::Float, loc, scale); });
+            })};
+            check_error(error_p, "GumbelRandom", &[])?;
+            Ok(variable)
+        }
     }
 
-    pub fn parameter_to_vec(&self) -> Vec<f32> {
-        assert!(self.is_parameter());
+    pub fn data_type(&self) -> DataType {
         let payload = self.payload;
-        let total_size = unsafe {
-            cpp!([payload as "Parameter"] -> usize as "size_t" {
-                return payload.Value()->Shape().TotalSize();
-            })
-        };
-        let mut buffer: Vec<f32> = Vec::with_capacity(total_size);
-        unsafe { buffer.set_len(total_size); }
-        let data = unsafe {
-            cpp!([payload as "Parameter"] -> *const f32 as "const float*" {
-              return payload.Value()->DataBuffer<float>();
+        DataType::from_raw(unsafe {
+            cpp!([payload as "Variable"] -> u32 as "unsigned int" {
+                return (unsigned int)payload.GetDataType();
             })
-        };
-        unsafe {
-            ptr::copy(data, buffer.as_mut_ptr(), total_size);
-        }
-        buffer
+        })
     }
 }
 
@@ -315,6 +660,32 @@ impl Clone for Variable {
     }
 }
 
+impl PartialEq for Variable {
+    fn eq(&self, other: &Variable) -> bool {
+        let payload = self.payload;
+        let opayload = other.payload;
+        unsafe {
+            cpp!([payload as "Variable", opayload as "Variable"] -> bool as "bool" {
+                return payload == opayload;
+            })
+        }
+    }
+}
+
+impl Eq for Variable {}
+
+impl Hash for Variable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let payload = self.payload;
+        let hash = unsafe {
+            cpp!([payload as "Variable"] -> usize as "size_t" {
+                return std::hash<Variable>()(payload);
+            })
+        };
+        state.write_usize(hash);
+    }
+}
+
 impl Drop for Variable {
     fn drop(&mut self) {
         let payload = self.payload;
@@ -349,3 +720,168 @@ impl<'a> From<&'a Variable> for Variable {
     }
 }
 
+
+macro_rules! typed_variable {
+    ($(#[$attr:meta])* $name:ident, $kind:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name(Variable);
+
+        impl $name {
+            /// Checks the kind of `variable`, returns None if it does not match.
+            pub fn from_variable(variable: Variable) -> Option<$name> {
+                if variable.kind() == $kind { Some($name(variable)) } else { None }
+            }
+
+            pub fn to_variable(&self) -> Variable {
+                self.0.clone()
+            }
+        }
+
+        impl Deref for $name {
+            type Target = Variable;
+
+            fn deref(&self) -> &Variable {
+                &self.0
+            }
+        }
+
+        impl Borrow<Variable> for $name {
+            fn borrow(&self) -> &Variable {
+                &self.0
+            }
+        }
+
+        impl<'a> Borrow<Variable> for &'a $name {
+            fn borrow(&self) -> &Variable {
+                &self.0
+            }
+        }
+
+        impl From<$name> for Variable {
+            fn from(f: $name) -> Variable {
+                f.0
+            }
+        }
+
+        impl<'a> From<&'a $name> for Variable {
+            fn from(f: &'a $name) -> Variable {
+                f.0.clone()
+            }
+        }
+    }
+}
+
+typed_variable!(
+    /// Trainable Variable, created by `Variable::parameter` or returned by `Function::parameters`.
+    Parameter, VariableKind::Parameter);
+typed_variable!(
+    /// Variable with fixed value, created by `Variable::constant_*`.
+    Constant, VariableKind::Constant);
+typed_variable!(
+    /// Variable fed with data through DataMap, created by `Variable::input_variable*` or `Variable::create`.
+    InputVariable, VariableKind::Input);
+typed_variable!(
+    /// Variable to be replaced using `Function::replace_placeholders`, created by `Variable::placeholder`.
+    Placeholder, VariableKind::Placeholder);
+
+impl Parameter {
+    /// Data of the parameter (without copying)
+    pub fn value(&self) -> NDArrayView {
+        self.try_value().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_value(&self) -> Result<NDArrayView, Error> {
+        let payload = self.0.payload;
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let view = NDArrayView { payload: cpp!([payload as "Variable", mut error_p as "char*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return cntk_rs_guard(error_p, [&] { return Parameter(payload).Value(); });
+            })};
+            check_error(error_p, "Parameter::Value", &[&self.0])?;
+            Ok(view)
+        }
+    }
+
+    pub fn parameter_to_vec(&self) -> Vec<f32> {
+        self.parameter_to_vec_of::<f32>()
+    }
+
+    /// Copies current value of the parameter. Panics if `T` does not match the data type of the parameter.
+    pub fn parameter_to_vec_of<T: CntkElement>(&self) -> Vec<T> {
+        self.value().to_vec_of::<T>()
+    }
+
+    /// Copies current value of the parameter into ndarray (with reversed shape of the parameter).
+    pub fn parameter_value(&self) -> ArrayD<f32> {
+        self.parameter_value_of::<f32>()
+    }
+
+    /// Panics if `T` does not match the data type of the parameter.
+    pub fn parameter_value_of<T: CntkElement>(&self) -> ArrayD<T> {
+        self.try_parameter_value_of::<T>().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_parameter_value(&self) -> Result<ArrayD<f32>, Error> {
+        self.try_parameter_value_of::<f32>()
+    }
+
+    pub fn try_parameter_value_of<T: CntkElement>(&self) -> Result<ArrayD<T>, Error> {
+        self.try_value()?.try_to_ndarray_of::<T>()
+    }
+
+    /// Overwrites value of the parameter. Shape of ndarray must be reversed shape of the parameter.
+    pub fn set_parameter_value<T: CntkElement, D: Dimension, S: Data<Elem=T>>(&self, value: &ArrayBase<S, D>) {
+        self.try_set_parameter_value(value).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_set_parameter_value<T: CntkElement, D: Dimension, S: Data<Elem=T>>(&self, value: &ArrayBase<S, D>) -> Result<(), Error> {
+        let view = NDArrayView::try_from_ndarray(&self.shape(), value, DeviceDescriptor::cpu())?;
+        let payload = self.0.payload;
+        let vpayload = view.payload;
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            cpp!([payload as "Variable", vpayload as "NDArrayViewPtr", mut error_p as "char*"] {
+                cntk_rs_guard(error_p, [&] { Parameter(payload).SetValue(vpayload); });
+            });
+            check_error(error_p, "Parameter::SetValue", &[&self.0])
+        }
+    }
+}
+
+impl Constant {
+    /// Data of the constant (without copying)
+    pub fn value(&self) -> NDArrayView {
+        self.try_value().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_value(&self) -> Result<NDArrayView, Error> {
+        let payload = self.0.payload;
+        unsafe {
+            let mut error_p: *mut c_char = ptr::null_mut();
+            let view = NDArrayView { payload: cpp!([payload as "Variable", mut error_p as "char*"] -> NDArrayViewInner as "NDArrayViewPtr" {
+                return cntk_rs_guard(error_p, [&] { return Constant(payload).Value(); });
+            })};
+            check_error(error_p, "Constant::Value", &[&self.0])?;
+            Ok(view)
+        }
+    }
+
+    /// Copies value of the constant into ndarray (with reversed shape of the constant).
+    pub fn constant_value(&self) -> ArrayD<f32> {
+        self.constant_value_of::<f32>()
+    }
+
+    /// Panics if `T` does not match the data type of the constant.
+    pub fn constant_value_of<T: CntkElement>(&self) -> ArrayD<T> {
+        self.try_constant_value_of::<T>().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_constant_value(&self) -> Result<ArrayD<f32>, Error> {
+        self.try_constant_value_of::<f32>()
+    }
+
+    pub fn try_constant_value_of<T: CntkElement>(&self) -> Result<ArrayD<T>, Error> {
+        self.try_value()?.try_to_ndarray_of::<T>()
+    }
+}